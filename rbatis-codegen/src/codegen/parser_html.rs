@@ -205,10 +205,11 @@ fn parse(
 
                 let mut replaced = HashMap::<String, bool>::new();
                 for (k, v) in convert_list {
+                    let (mode, expr) = parse_replace_mode(&k, &v);
                     let method_impl = crate::codegen::func::impl_fn(
                         &body.to_string(),
                         "",
-                        &format!("\"{}\"", k),
+                        &format!("\"{}\"", expr),
                         false,
                         true,
                         ignore,
@@ -221,7 +222,13 @@ fn parse(
                         };
                     } else {
                         if replaced.get(&v).is_none() {
-                            replaces = quote! {#replaces.replacen(#v, &#method_impl.as_sql(), 1)};
+                            let value = match mode {
+                                ReplaceMode::Raw => quote! {#method_impl.as_sql()},
+                                ReplaceMode::Ident(whitelist) => quote! {
+                                    rbatis_codegen::from_sql::ident_sql(&#method_impl, #expr, &[#(#whitelist),*])?
+                                },
+                            };
+                            replaces = quote! {#replaces.replacen(#v, &#value, 1)};
                             replaced.insert(v.to_string(), true);
                         }
                     }
@@ -487,13 +494,13 @@ fn parse(
                 let method_name = Ident::new(id, Span::call_site());
                 let child_body = parse(&x.childs, methods, "select", ignore);
                 let select = quote! {
-                    pub fn #method_name (arg:&rbs::Value, _tag: char) -> (String,Vec<rbs::Value>) {
+                    pub fn #method_name (arg:&rbs::Value, _tag: char) -> Result<(String,Vec<rbs::Value>), rbatis_codegen::error::Error> {
                        use rbatis_codegen::ops::*;
                        let mut sql = String::with_capacity(1000);
                        let mut args = Vec::with_capacity(20);
                        #child_body
                        #fix_sql
-                       return (sql,args);
+                       return Ok((sql,args));
                     }
                 };
                 body = quote! {
@@ -509,14 +516,14 @@ fn parse(
                 let method_name = Ident::new(id, Span::call_site());
                 let child_body = parse(&x.childs, methods, "select", ignore);
                 let select = quote! {
-                    pub fn #method_name (arg:&rbs::Value, _tag: char) -> (String,Vec<rbs::Value>) {
+                    pub fn #method_name (arg:&rbs::Value, _tag: char) -> Result<(String,Vec<rbs::Value>), rbatis_codegen::error::Error> {
                        use rbatis_codegen::ops::*;
 
                        let mut sql = String::with_capacity(1000);
                        let mut args = Vec::with_capacity(20);
                        #child_body
                        #fix_sql
-                       return (sql,args);
+                       return Ok((sql,args));
                     }
                 };
                 body = quote! {
@@ -532,14 +539,14 @@ fn parse(
                 let method_name = Ident::new(id, Span::call_site());
                 let child_body = parse(&x.childs, methods, "select", ignore);
                 let select = quote! {
-                    pub fn #method_name (arg:&rbs::Value, _tag: char) -> (String,Vec<rbs::Value>) {
+                    pub fn #method_name (arg:&rbs::Value, _tag: char) -> Result<(String,Vec<rbs::Value>), rbatis_codegen::error::Error> {
                        use rbatis_codegen::ops::*;
 
                        let mut sql = String::with_capacity(1000);
                        let mut args = Vec::with_capacity(20);
                        #child_body
                        #fix_sql
-                       return (sql,args);
+                       return Ok((sql,args));
                     }
                 };
                 body = quote! {
@@ -555,14 +562,14 @@ fn parse(
                 let method_name = Ident::new(id, Span::call_site());
                 let child_body = parse(&x.childs, methods, "select", ignore);
                 let select = quote! {
-                    pub fn #method_name (arg:&rbs::Value, _tag: char) -> (String,Vec<rbs::Value>) {
+                    pub fn #method_name (arg:&rbs::Value, _tag: char) -> Result<(String,Vec<rbs::Value>), rbatis_codegen::error::Error> {
                        use rbatis_codegen::ops::*;
 
                        let mut sql = String::with_capacity(1000);
                        let mut args = Vec::with_capacity(20);
                        #child_body
                        #fix_sql
-                       return (sql,args);
+                       return Ok((sql,args));
                     }
                 };
                 body = quote! {
@@ -577,6 +584,34 @@ fn parse(
     return body.into();
 }
 
+enum ReplaceMode {
+    Raw,
+    Ident(Vec<String>),
+}
+
+/// `${raw:expr}` splices the raw value, `${ident[a,b]:expr}` only allows `a` or `b`,
+/// `${expr}` must be an identifier
+fn parse_replace_mode(k: &str, v: &str) -> (ReplaceMode, String) {
+    let k = k.trim();
+    if v.starts_with("#") {
+        return (ReplaceMode::Raw, k.to_string());
+    }
+    if let Some(expr) = k.strip_prefix("raw:") {
+        return (ReplaceMode::Raw, expr.trim().to_string());
+    }
+    if let Some(rest) = k.strip_prefix("ident[") {
+        if let Some((list, expr)) = rest.split_once("]:") {
+            let whitelist = list
+                .split(',')
+                .map(|x| x.trim().trim_matches('\'').to_string())
+                .filter(|x| !x.is_empty())
+                .collect();
+            return (ReplaceMode::Ident(whitelist), expr.trim().to_string());
+        }
+    }
+    (ReplaceMode::Ident(vec![]), k.to_string())
+}
+
 fn impl_println(x: &Element, body: &mut proc_macro2::TokenStream, ignore: &mut Vec<String>) {
     let value = x
         .attrs
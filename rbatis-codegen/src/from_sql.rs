@@ -1,3 +1,4 @@
+use crate::error::Error;
 use crate::ops::{AsProxy, AsSql};
 use rbs::Value;
 
@@ -43,3 +44,121 @@ to_sql!([&str && str]);
 to_sql!([i8 i16 i32 i64 isize]);
 to_sql!([u8 u16 u32 u64 usize]);
 to_sql!([f32 f64]);
+
+/// the sql of a `${}` substitution, which must be an identifier such as `name` or `t.name`.
+/// a part may already be quoted (`"a b"`, `` `a` ``, `[a]`) without a backslash and numbers are allowed,
+/// anything else is an error. a non empty `whitelist` only allows the identifiers listed.
+///
+/// `${raw:expr}` skips this check and splices the raw value.
+pub fn ident_sql<T: AsSql>(v: T, expr: &str, whitelist: &[&str]) -> Result<String, Error> {
+    let sql = v.as_sql();
+    if !whitelist.is_empty() {
+        if whitelist.contains(&sql.as_str()) {
+            return Ok(sql);
+        }
+        return Err(Error::from(format!(
+            "[rbatis] `${{{}}}` = '{}' is not one of {:?}",
+            expr, sql, whitelist
+        )));
+    }
+    if is_ident(&sql) || is_number(&sql) {
+        Ok(sql)
+    } else {
+        Err(Error::from(format!(
+            "[rbatis] `${{{}}}` = '{}' is not an identifier, use `${{raw:{}}}` to splice raw sql",
+            expr, sql, expr
+        )))
+    }
+}
+
+/// `a`, `a.b`, `"a b".c`, `` `a` `` or `[a]`, a bare part is `[A-Za-z_][A-Za-z0-9_$]*`.
+///
+/// a quoted part has no backslash, mysql reads `"a\""` as a string literal with a backslash escape
+pub fn is_ident(s: &str) -> bool {
+    let mut chars = s.chars().peekable();
+    loop {
+        match chars.next() {
+            Some(open @ ('"' | '`' | '[')) => {
+                let close = if open == '[' { ']' } else { open };
+                let mut empty = true;
+                loop {
+                    match chars.next() {
+                        None | Some('\\') => return false,
+                        Some(c) if c == close => {
+                            if chars.peek() == Some(&close) {
+                                chars.next();
+                            } else {
+                                break;
+                            }
+                        }
+                        Some(_) => {}
+                    }
+                    empty = false;
+                }
+                if empty {
+                    return false;
+                }
+            }
+            Some(c) if c.is_ascii_alphabetic() || c == '_' => {
+                while let Some(c) = chars.peek() {
+                    if c.is_ascii_alphanumeric() || *c == '_' || *c == '$' {
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+            }
+            _ => return false,
+        }
+        match chars.next() {
+            None => return true,
+            Some('.') => {}
+            Some(_) => return false,
+        }
+    }
+}
+
+fn is_number(s: &str) -> bool {
+    let s = s.strip_prefix('-').unwrap_or(s);
+    !s.is_empty()
+        && s.chars().all(|c| c.is_ascii_digit() || c == '.')
+        && s.chars().filter(|c| *c == '.').count() <= 1
+        && s != "."
+}
+
+#[cfg(test)]
+mod test {
+    use crate::from_sql::{ident_sql, is_ident};
+
+    #[test]
+    fn test_is_ident() {
+        for s in ["id", "t.id", "_a1", "a$1", "\"a b\".c", "`a``b`", "[t]]]", "\"名字\""] {
+            assert!(is_ident(s), "{}", s);
+        }
+        for s in [
+            "",
+            "1a",
+            "a b",
+            "a;drop table t",
+            "a.",
+            "\"a",
+            "\"\"",
+            "a--",
+            "[a]b",
+            "名字",
+            "\"x\\\"\", (select sleep(5)) -- \"",
+            "`a\\`",
+        ] {
+            assert!(!is_ident(s), "{}", s);
+        }
+    }
+
+    #[test]
+    fn test_ident_sql() {
+        assert_eq!(ident_sql("name", "column", &[]).unwrap(), "name");
+        assert_eq!(ident_sql(10, "limit", &[]).unwrap(), "10");
+        assert!(ident_sql("id = 1 or 1 = 1", "column", &[]).is_err());
+        assert_eq!(ident_sql("id", "column", &["id", "name"]).unwrap(), "id");
+        assert!(ident_sql("age", "column", &["id", "name"]).is_err());
+    }
+}
@@ -1,11 +1,12 @@
 #![allow(unused_assignments)]
 extern crate proc_macro;
 extern crate rbatis_codegen;
-use syn::{parse_macro_input, AttributeArgs, ItemFn};
+use syn::{parse_macro_input, AttributeArgs, DeriveInput, ItemFn};
 
 use crate::macros::html_sql_impl::impl_macro_html_sql;
 use crate::macros::py_sql_impl::impl_macro_py_sql;
 use crate::macros::sql_impl::impl_macro_sql;
+use crate::macros::table_impl::impl_crud_table;
 use crate::proc_macro::TokenStream;
 
 mod macros;
@@ -113,6 +114,39 @@ pub fn html_sql(args: TokenStream, func: TokenStream) -> TokenStream {
     stream
 }
 
+/// impl `rbatis::table::Table` and the crud methods insert, insert_batch, select_all,
+/// select_by_column, update_by_column(_batch), delete_by_column(_batch) and select_page
+///
+/// #[derive(serde::Serialize, serde::Deserialize, CRUDTable)]
+/// #[table(name = "biz_activity", schema = "public")]
+/// pub struct BizActivity {
+///     #[column(id)]
+///     pub id: Option<String>,
+///     #[column(rename = "activity_name", skip_update)]
+///     pub name: Option<String>,
+///     #[column(version)]
+///     pub version: Option<i64>,
+///     #[column(logic_delete, skip_insert)]
+///     pub delete_flag: Option<i32>,
+///     //not a column, BizActivity::load_comments(&mut rb, &mut activities) sets it with one `in` query.
+///     //a has_many Vec without #[serde(default)] (or an Option<Vec<..>>) is a compile error
+///     #[relation(has_many, foreign_key = "activity_id")]
+///     #[serde(default)]
+///     pub comments: Vec<Comment>,
+/// }
+///
+#[proc_macro_derive(CRUDTable, attributes(table, column, relation))]
+pub fn crud_table(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let stream = impl_crud_table(&input).into();
+    #[cfg(feature = "debug_mode")]
+    {
+        println!("............gen macro CRUDTable :\n {}", stream);
+        println!("............gen macro CRUDTable end............");
+    }
+    stream
+}
+
 /// proxy rbatis_codegen rb_py
 #[proc_macro_attribute]
 pub fn rb_py(args: TokenStream, func: TokenStream) -> TokenStream {
@@ -5,7 +5,7 @@ use syn::{AttributeArgs, FnArg, ItemFn, Lit, NestedMeta};
 
 use crate::macros::py_sql_impl;
 use crate::proc_macro::TokenStream;
-use crate::util::{find_fn_body, find_return_type, get_fn_args, is_fetch, is_rbatis_ref};
+use crate::util::{find_fn_body, find_return_type, get_fn_args, is_fetch, is_page, is_rbatis_ref};
 
 pub(crate) fn impl_macro_html_sql(target_fn: &ItemFn, args: &AttributeArgs) -> TokenStream {
     let return_ty = find_return_type(target_fn);
@@ -77,7 +77,9 @@ pub(crate) fn impl_macro_html_sql(target_fn: &ItemFn, args: &AttributeArgs) -> T
     let sql_args_gen = py_sql_impl::filter_args_context_id(&rbatis_name, &get_fn_args(target_fn));
     let is_fetch = is_fetch(&return_ty.to_string());
     let mut call_method = quote! {};
-    if is_fetch {
+    if is_page(&return_ty.to_string()) {
+        call_method = py_sql_impl::page_call_method(target_fn, args, &rbatis_ident);
+    } else if is_fetch {
         call_method = quote! {
              use rbatis::executor::{Executor};
              let r=#rbatis_ident.fetch(&sql,rb_args).await?;
@@ -108,7 +110,7 @@ pub(crate) fn impl_macro_html_sql(target_fn: &ItemFn, args: &AttributeArgs) -> T
          let driver_type = #rbatis_ident.get_rbatis().driver_type()?;
          use rbatis::rbatis_codegen;
          #gen_func
-         let (mut sql,rb_args) = #func_name_ident(&rbs::Value::Map(rb_arg_map),'?');
+         let (mut sql,rb_args) = #func_name_ident(&rbs::Value::Map(rb_arg_map),'?').map_err(|e| rbatis::Error::from(e.to_string()))?;
          #call_method
        }
     }
@@ -1,3 +1,4 @@
 pub mod html_sql_impl;
 pub mod py_sql_impl;
 pub mod sql_impl;
+pub mod table_impl;
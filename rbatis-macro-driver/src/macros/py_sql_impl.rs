@@ -1,14 +1,14 @@
 use proc_macro2::{Ident, Span};
 use quote::quote;
 use quote::ToTokens;
-use syn::{AttributeArgs, FnArg, ItemFn, Lit,NestedMeta, Pat};
+use syn::{AttributeArgs, FnArg, ItemFn, Lit, Meta, NestedMeta, Pat};
 
 use crate::proc_macro::TokenStream;
-use crate::util::{find_fn_body, find_return_type, get_fn_args, is_fetch, is_rbatis_ref};
+use crate::util::{find_fn_body, find_page_req, find_return_type, get_fn_args, is_fetch, is_page, is_rbatis_ref};
 
 ///py_sql macro
 ///support args for RB:&Rbatis,page:&PageRequest
-///support return for Page<*>
+///support return for Page<*>, the select is paged by the dialect and counted, `sortable = "a,b"` columns sort it
 pub(crate) fn impl_macro_py_sql(target_fn: &ItemFn, args: &AttributeArgs) -> TokenStream {
     let return_ty = find_return_type(target_fn);
     let mut rbatis_ident = "".to_token_stream();
@@ -78,7 +78,9 @@ pub(crate) fn impl_macro_py_sql(target_fn: &ItemFn, args: &AttributeArgs) -> Tok
     let sql_args_gen = filter_args_context_id(&rbatis_name, &get_fn_args(target_fn));
     let is_fetch = is_fetch(&return_ty.to_string());
     let mut call_method = quote! {};
-    if is_fetch {
+    if is_page(&return_ty.to_string()) {
+        call_method = page_call_method(target_fn, args, &rbatis_ident);
+    } else if is_fetch {
         call_method = quote! {
              use rbatis::executor::{Executor};
              let r=#rbatis_ident.fetch(&sql,rb_args).await?;
@@ -109,13 +111,52 @@ pub(crate) fn impl_macro_py_sql(target_fn: &ItemFn, args: &AttributeArgs) -> Tok
          let driver_type = #rbatis_ident.get_rbatis().driver_type()?;
          use rbatis::rbatis_codegen;
          #gen_func
-         let (mut sql,rb_args) = #func_name_ident(&rbs::Value::Map(rb_arg_map), '?');
+         let (mut sql,rb_args) = #func_name_ident(&rbs::Value::Map(rb_arg_map),'?').map_err(|e| rbatis::Error::from(e.to_string()))?;
          #call_method
        }
     }
         .into();
 }
 
+//fetch the `Page<T>` of the rendered select, the total is a count of it.
+//`sortable = "a,b"` are the columns of a `PageRequest::order_by`
+pub(crate) fn page_call_method(
+    target_fn: &ItemFn,
+    args: &AttributeArgs,
+    rbatis_ident: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let mut sortable = vec![];
+    for ele in args {
+        if let NestedMeta::Meta(Meta::NameValue(nv)) = ele {
+            match (nv.path.get_ident(), &nv.lit) {
+                (Some(k), Lit::Str(v)) if k == "sortable" => {
+                    for c in v.value().split(',').map(|c| c.trim()).filter(|c| !c.is_empty()) {
+                        sortable.push(c.to_string());
+                    }
+                }
+                _ => panic!("[rbatis] unknown arg on '{}()', use sortable = \"a,b\"", target_fn.sig.ident),
+            }
+        }
+    }
+    let page_req = match find_page_req(target_fn) {
+        Some(page_req) => page_req,
+        None => panic!(
+            "[rbatis] you should add page param  page_req: &PageRequest  on '{}()' returning Page!",
+            target_fn.sig.ident
+        ),
+    };
+    quote! {
+         use rbatis::executor::{Executor};
+         let page_sql = rbatis::sql::page_sql(#rbatis_ident.dialect()?, &#page_req, &[#(#sortable),*], &sql, rb_args)?;
+         let total: u64 = match page_sql.count {
+             Some((count_sql, count_args)) => rbatis::decode::decode(#rbatis_ident.fetch(&count_sql, count_args).await?)?,
+             None => #page_req.total,
+         };
+         let r = #rbatis_ident.fetch(&page_sql.sql, page_sql.args).await?;
+         Ok(rbatis::sql::Page::from_rows(&#page_req, total, r)?)
+    }
+}
+
 pub(crate) fn filter_args_context_id(
     rbatis_name: &str,
     fn_arg_name_vec: &Vec<Box<Pat>>,
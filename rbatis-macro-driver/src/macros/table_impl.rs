@@ -0,0 +1,546 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+use syn::{
+    Attribute, Data, DeriveInput, Field, Fields, GenericArgument, Lit, Meta, NestedMeta, PathArguments, Type,
+};
+
+use crate::util::to_snake_name;
+
+#[derive(Default)]
+struct ColumnAttr {
+    field: String,
+    name: String,
+    id: bool,
+    skip_insert: bool,
+    skip_update: bool,
+    version: bool,
+    logic_delete: bool,
+}
+
+/// impl `rbatis::table::Table` and the crud methods for `#[derive(CRUDTable)]`
+pub(crate) fn impl_crud_table(input: &DeriveInput) -> TokenStream {
+    let ident = &input.ident;
+    let mut table_name = to_snake_name(&ident.to_string());
+    let mut schema = None;
+    for (k, v) in attr_args(&input.attrs, "table") {
+        match (k.as_str(), v) {
+            ("name", Some(v)) => table_name = v,
+            ("schema", Some(v)) => schema = Some(v),
+            _ => panic!("[rbatis] unknown #[table({})], use #[table(name = \"..\", schema = \"..\")]", k),
+        }
+    }
+    let fields = match &input.data {
+        Data::Struct(s) => match &s.fields {
+            Fields::Named(f) => &f.named,
+            _ => panic!("[rbatis] #[derive(CRUDTable)] needs a struct with named fields"),
+        },
+        _ => panic!("[rbatis] #[derive(CRUDTable)] needs a struct"),
+    };
+    let mut columns = vec![];
+    let mut id_types = vec![];
+    let mut id_fields = vec![];
+    let mut field_refs = vec![];
+    let mut relations = vec![];
+    for f in fields {
+        let relation = attr_args(&f.attrs, "relation");
+        if !relation.is_empty() {
+            relations.push((f, relation));
+            continue;
+        }
+        let mut c = ColumnAttr {
+            field: f.ident.as_ref().unwrap().to_string().trim_start_matches("r#").to_string(),
+            ..Default::default()
+        };
+        for (k, v) in attr_args(&f.attrs, "serde") {
+            if k == "rename" {
+                if let Some(v) = v {
+                    c.field = v;
+                }
+            }
+        }
+        c.name = c.field.clone();
+        for (k, v) in attr_args(&f.attrs, "column") {
+            match (k.as_str(), v) {
+                ("id", None) => c.id = true,
+                ("rename", Some(v)) => c.name = v,
+                ("skip_insert", None) => c.skip_insert = true,
+                ("skip_update", None) => c.skip_update = true,
+                ("version", None) => c.version = true,
+                ("logic_delete", None) => c.logic_delete = true,
+                _ => panic!(
+                    "[rbatis] unknown #[column({})], use id, rename = \"..\", skip_insert, skip_update, version or logic_delete",
+                    k
+                ),
+            }
+        }
+        if c.id {
+            id_types.push(strip_option(&f.ty));
+            id_fields.push(f.ident.clone().unwrap());
+        }
+        let field_ident = f.ident.as_ref().unwrap();
+        let field_ty = strip_option(&f.ty);
+        let (name, field) = (&c.name, &c.field);
+        field_refs.push(quote! {
+            pub const fn #field_ident() -> rbatis::table::Column<#ident, #field_ty> {
+                rbatis::table::Column::new(#name, #field)
+            }
+        });
+        columns.push(c);
+    }
+    let column_names = columns.iter().map(|c| &c.name);
+    let columns = columns.iter().map(|c| {
+        let ColumnAttr {
+            field,
+            name,
+            id,
+            skip_insert,
+            skip_update,
+            version,
+            logic_delete,
+        } = c;
+        quote! {
+            rbatis::table::ColumnMeta {
+                field: #field,
+                name: #name,
+                id: #id,
+                skip_insert: #skip_insert,
+                skip_update: #skip_update,
+                version: #version,
+                logic_delete: #logic_delete,
+            }
+        }
+    });
+    let struct_default = serde_default(&input.attrs);
+    let relations = relations
+        .into_iter()
+        .map(|(f, args)| relation_method(ident, f, args, &id_fields, struct_default));
+    let schema = match schema {
+        None => quote! {None},
+        Some(s) => quote! {Some(#s)},
+    };
+    let table = ident;
+    let by_id = match id_types.len() {
+        0 => quote! {},
+        n => {
+            let key = if n == 1 {
+                quote! {#(#id_types)*}
+            } else {
+                quote! {(#(#id_types),*)}
+            };
+            quote! {
+                impl #table {
+                    pub async fn select_by_id(
+                        rb: &mut dyn rbatis::executor::Executor,
+                        id: #key,
+                    ) -> Result<Option<#table>, rbatis::Error> {
+                        rbatis::table::select_by_id(rb, id).await
+                    }
+
+                    pub async fn select_by_id_batch(
+                        rb: &mut dyn rbatis::executor::Executor,
+                        ids: &[#key],
+                    ) -> Result<Vec<#table>, rbatis::Error> {
+                        rbatis::table::select_by_id_batch(rb, ids).await
+                    }
+
+                    pub async fn update_by_id(
+                        rb: &mut dyn rbatis::executor::Executor,
+                        table: &#table,
+                    ) -> Result<rbdc::db::ExecResult, rbatis::Error> {
+                        rbatis::table::update_by_id(rb, table).await
+                    }
+
+                    pub async fn update_by_id_batch(
+                        rb: &mut dyn rbatis::executor::Executor,
+                        tables: &[#table],
+                    ) -> Result<rbdc::db::ExecResult, rbatis::Error> {
+                        rbatis::table::update_by_id_batch(rb, tables).await
+                    }
+
+                    pub async fn delete_by_id(
+                        rb: &mut dyn rbatis::executor::Executor,
+                        id: #key,
+                    ) -> Result<rbdc::db::ExecResult, rbatis::Error> {
+                        rbatis::table::delete_by_id::<#table, #key>(rb, id).await
+                    }
+
+                    pub async fn delete_by_id_batch(
+                        rb: &mut dyn rbatis::executor::Executor,
+                        ids: &[#key],
+                    ) -> Result<rbdc::db::ExecResult, rbatis::Error> {
+                        rbatis::table::delete_by_id_batch::<#table, #key>(rb, ids).await
+                    }
+                }
+            }
+        }
+    };
+    quote! {
+        impl rbatis::table::Table for #table {
+            fn table_name() -> &'static str {
+                #table_name
+            }
+
+            fn table_schema() -> Option<&'static str> {
+                #schema
+            }
+
+            fn table_columns() -> &'static [rbatis::table::ColumnMeta] {
+                &[#(#columns),*]
+            }
+        }
+
+        impl #table {
+            /// the column names
+            pub const COLUMNS: &'static [&'static str] = &[#(#column_names),*];
+
+            #(#field_refs)*
+
+            pub async fn insert(
+                rb: &mut dyn rbatis::executor::Executor,
+                table: &#table,
+            ) -> Result<rbdc::db::ExecResult, rbatis::Error> {
+                rbatis::table::insert_batch(rb, std::slice::from_ref(table)).await
+            }
+
+            pub async fn insert_batch(
+                rb: &mut dyn rbatis::executor::Executor,
+                tables: &[#table],
+            ) -> Result<rbdc::db::ExecResult, rbatis::Error> {
+                rbatis::table::insert_batch(rb, tables).await
+            }
+
+            /// insert `chunk_size` tables a statement, 0 is the most under `dialect.max_params()`
+            pub async fn insert_batch_chunked(
+                rb: &mut dyn rbatis::executor::Executor,
+                tables: &[#table],
+                chunk_size: usize,
+            ) -> Result<rbdc::db::ExecResult, rbatis::Error> {
+                rbatis::table::insert_batch_chunked(rb, tables, chunk_size).await
+            }
+
+            /// insert and write the generated keys and defaults back into `table`
+            pub async fn insert_returning(
+                rb: &mut dyn rbatis::executor::Executor,
+                table: &mut #table,
+            ) -> Result<rbdc::db::ExecResult, rbatis::Error> {
+                rbatis::table::insert_returning(rb, std::slice::from_mut(table)).await
+            }
+
+            pub async fn insert_batch_returning(
+                rb: &mut dyn rbatis::executor::Executor,
+                tables: &mut [#table],
+            ) -> Result<rbdc::db::ExecResult, rbatis::Error> {
+                rbatis::table::insert_returning(rb, tables).await
+            }
+
+            /// insert, or update the row conflicting on the `conflict_columns`
+            pub async fn insert_or_update(
+                rb: &mut dyn rbatis::executor::Executor,
+                table: &#table,
+                conflict_columns: &[&str],
+                on_conflict: rbatis::crud::OnConflict,
+            ) -> Result<rbdc::db::ExecResult, rbatis::Error> {
+                rbatis::table::insert_or_update(rb, std::slice::from_ref(table), conflict_columns, on_conflict).await
+            }
+
+            pub async fn insert_or_update_batch(
+                rb: &mut dyn rbatis::executor::Executor,
+                tables: &[#table],
+                conflict_columns: &[&str],
+                on_conflict: rbatis::crud::OnConflict,
+            ) -> Result<rbdc::db::ExecResult, rbatis::Error> {
+                rbatis::table::insert_or_update(rb, tables, conflict_columns, on_conflict).await
+            }
+
+            pub async fn select_all(
+                rb: &mut dyn rbatis::executor::Executor,
+            ) -> Result<Vec<#table>, rbatis::Error> {
+                rbatis::table::select_all(rb).await
+            }
+
+            /// the `*_by_column` methods take a column of this table such as `Self::id()`,
+            /// `rbatis::table::select_by_column::<Self, _>(rb, "id", ..)` takes a column name
+            pub async fn select_by_column<C, V: serde::Serialize>(
+                rb: &mut dyn rbatis::executor::Executor,
+                column: rbatis::table::Column<#table, C>,
+                column_value: V,
+            ) -> Result<Vec<#table>, rbatis::Error> {
+                rbatis::table::select_by_column(rb, column.name, column_value).await
+            }
+
+            pub async fn update_by_column<C>(
+                rb: &mut dyn rbatis::executor::Executor,
+                table: &#table,
+                column: rbatis::table::Column<#table, C>,
+            ) -> Result<rbdc::db::ExecResult, rbatis::Error> {
+                rbatis::table::update_by_column(rb, table, column.name).await
+            }
+
+            /// update every column but `skip_update`, null columns are set to null
+            pub async fn update_by_column_with_nulls<C>(
+                rb: &mut dyn rbatis::executor::Executor,
+                table: &#table,
+                column: rbatis::table::Column<#table, C>,
+            ) -> Result<rbdc::db::ExecResult, rbatis::Error> {
+                rbatis::table::update_by_column_with_nulls(rb, table, column.name).await
+            }
+
+            pub async fn update_by_column_batch<C>(
+                rb: &mut dyn rbatis::executor::Executor,
+                tables: &[#table],
+                column: rbatis::table::Column<#table, C>,
+            ) -> Result<rbdc::db::ExecResult, rbatis::Error> {
+                rbatis::table::update_by_column_batch(rb, tables, column.name).await
+            }
+
+            pub async fn delete_by_column<C, V: serde::Serialize>(
+                rb: &mut dyn rbatis::executor::Executor,
+                column: rbatis::table::Column<#table, C>,
+                column_value: V,
+            ) -> Result<rbdc::db::ExecResult, rbatis::Error> {
+                rbatis::table::delete_by_column::<#table, V>(rb, column.name, column_value).await
+            }
+
+            pub async fn delete_by_column_batch<C, V: serde::Serialize>(
+                rb: &mut dyn rbatis::executor::Executor,
+                column: rbatis::table::Column<#table, C>,
+                column_values: &[V],
+            ) -> Result<rbdc::db::ExecResult, rbatis::Error> {
+                rbatis::table::delete_by_column_batch::<#table, V>(rb, column.name, column_values).await
+            }
+
+            /// select by the wrapper, with its `group by`, `order by` and `limit`
+            pub async fn select_by_wrapper(
+                rb: &mut dyn rbatis::executor::Executor,
+                w: &rbatis::sql::QueryWrapper<#table>,
+            ) -> Result<Vec<#table>, rbatis::Error> {
+                rbatis::table::select_by_wrapper(rb, &w.clone().untyped()).await
+            }
+
+            pub async fn count_by_wrapper(
+                rb: &mut dyn rbatis::executor::Executor,
+                w: &rbatis::sql::QueryWrapper<#table>,
+            ) -> Result<u64, rbatis::Error> {
+                rbatis::table::count_by_wrapper::<#table>(rb, &w.clone().untyped()).await
+            }
+
+            pub async fn exists_by_wrapper(
+                rb: &mut dyn rbatis::executor::Executor,
+                w: &rbatis::sql::QueryWrapper<#table>,
+            ) -> Result<bool, rbatis::Error> {
+                rbatis::table::exists_by_wrapper::<#table>(rb, &w.clone().untyped()).await
+            }
+
+            /// select by the `and` of the entries of a map, an array value is `in (..)`, a null value `is null`
+            pub async fn select_by_map<M: serde::Serialize>(
+                rb: &mut dyn rbatis::executor::Executor,
+                condition: M,
+            ) -> Result<Vec<#table>, rbatis::Error> {
+                rbatis::table::select_by_map(rb, condition).await
+            }
+
+            pub async fn count_by_map<M: serde::Serialize>(
+                rb: &mut dyn rbatis::executor::Executor,
+                condition: M,
+            ) -> Result<u64, rbatis::Error> {
+                rbatis::table::count_by_map::<#table, M>(rb, condition).await
+            }
+
+            pub async fn exists_by_map<M: serde::Serialize>(
+                rb: &mut dyn rbatis::executor::Executor,
+                condition: M,
+            ) -> Result<bool, rbatis::Error> {
+                rbatis::table::exists_by_map::<#table, M>(rb, condition).await
+            }
+
+            pub async fn count_by_column<C, V: serde::Serialize>(
+                rb: &mut dyn rbatis::executor::Executor,
+                column: rbatis::table::Column<#table, C>,
+                column_value: V,
+            ) -> Result<u64, rbatis::Error> {
+                rbatis::table::count_by_column::<#table, V>(rb, column.name, column_value).await
+            }
+
+            pub async fn exists_by_column<C, V: serde::Serialize>(
+                rb: &mut dyn rbatis::executor::Executor,
+                column: rbatis::table::Column<#table, C>,
+                column_value: V,
+            ) -> Result<bool, rbatis::Error> {
+                rbatis::table::exists_by_column::<#table, V>(rb, column.name, column_value).await
+            }
+
+            pub async fn update_by_wrapper(
+                rb: &mut dyn rbatis::executor::Executor,
+                table: &#table,
+                w: &rbatis::sql::QueryWrapper<#table>,
+            ) -> Result<rbdc::db::ExecResult, rbatis::Error> {
+                rbatis::table::update_by_wrapper(rb, table, &w.clone().untyped()).await
+            }
+
+            pub async fn delete_by_wrapper(
+                rb: &mut dyn rbatis::executor::Executor,
+                w: &rbatis::sql::QueryWrapper<#table>,
+            ) -> Result<rbdc::db::ExecResult, rbatis::Error> {
+                rbatis::table::delete_by_wrapper::<#table>(rb, &w.clone().untyped()).await
+            }
+
+            pub async fn select_page(
+                rb: &mut dyn rbatis::executor::Executor,
+                page_req: &rbatis::sql::PageRequest,
+            ) -> Result<rbatis::sql::Page<#table>, rbatis::Error> {
+                rbatis::table::select_page(rb, page_req).await
+            }
+
+            #(#relations)*
+        }
+
+        #by_id
+    }
+}
+
+/// the `load_<field>` method of a `#[relation(has_many | belongs_to, foreign_key = "..", key = "..")]` field
+fn relation_method(
+    table: &Ident,
+    f: &Field,
+    args: Vec<(String, Option<String>)>,
+    id_fields: &[Ident],
+    struct_default: bool,
+) -> TokenStream {
+    let field = f.ident.as_ref().unwrap();
+    let mut kind = None;
+    let mut foreign_key = None;
+    let mut key = None;
+    for (k, v) in args {
+        match (k.as_str(), v) {
+            ("has_many", None) | ("belongs_to", None) => kind = Some(k),
+            ("foreign_key", Some(v)) => foreign_key = Some(v),
+            ("key", Some(v)) => key = Some(v),
+            _ => panic!(
+                "[rbatis] unknown #[relation({})], use has_many or belongs_to, foreign_key = \"..\", key = \"..\"",
+                k
+            ),
+        }
+    }
+    let foreign_key = foreign_key
+        .unwrap_or_else(|| panic!("[rbatis] #[relation] of `{}` needs foreign_key = \"..\"", field));
+    let method = format_ident!("load_{}", field.to_string().trim_start_matches("r#"));
+    let doc = format!(" load the `{}` of the tables with one `in` query", field);
+    match kind.as_deref() {
+        Some("has_many") => {
+            let key = match key {
+                Some(key) => format_ident!("{}", key),
+                None if id_fields.len() == 1 => id_fields[0].clone(),
+                None => panic!(
+                    "[rbatis] #[relation(has_many)] of `{}` needs key = \"..\" of a table without one #[column(id)]",
+                    field
+                ),
+            };
+            let child = inner_type(&strip_option(&f.ty), "Vec")
+                .unwrap_or_else(|| panic!("[rbatis] #[relation(has_many)] `{}` must be a Vec", field));
+            //the column is not in the select result, a bare Vec must deserialize without it
+            if inner_type(&f.ty, "Option").is_none() && !struct_default && !serde_default(&f.attrs) {
+                panic!(
+                    "[rbatis] #[relation(has_many)] `{}` needs #[serde(default)] or an Option<Vec<..>>",
+                    field
+                );
+            }
+            quote! {
+                #[doc = #doc]
+                pub async fn #method(
+                    rb: &mut dyn rbatis::executor::Executor,
+                    tables: &mut [#table],
+                ) -> Result<(), rbatis::Error> {
+                    rbatis::table::load_has_many::<#table, #child>(
+                        rb,
+                        tables,
+                        |t| rbs::to_value!(&t.#key),
+                        #foreign_key,
+                        |t, v| t.#field = v.into(),
+                    )
+                    .await
+                }
+            }
+        }
+        Some(_) => {
+            let key = key.unwrap_or_else(|| "id".to_string());
+            let foreign_key = format_ident!("{}", foreign_key);
+            let parent = inner_type(&f.ty, "Option")
+                .unwrap_or_else(|| panic!("[rbatis] #[relation(belongs_to)] `{}` must be an Option", field));
+            quote! {
+                #[doc = #doc]
+                pub async fn #method(
+                    rb: &mut dyn rbatis::executor::Executor,
+                    tables: &mut [#table],
+                ) -> Result<(), rbatis::Error> {
+                    rbatis::table::load_belongs_to::<#table, #parent>(
+                        rb,
+                        tables,
+                        |t| rbs::to_value!(&t.#foreign_key),
+                        #key,
+                        |t, v| t.#field = v,
+                    )
+                    .await
+                }
+            }
+        }
+        None => panic!("[rbatis] #[relation] of `{}` needs has_many or belongs_to", field),
+    }
+}
+
+/// has `#[serde(default)]`, `#[serde(default = "..")]` or skips deserializing
+fn serde_default(attrs: &[Attribute]) -> bool {
+    attr_args(attrs, "serde")
+        .iter()
+        .any(|(k, _)| matches!(k.as_str(), "default" | "skip" | "skip_deserializing"))
+}
+
+/// `Option<T>` -> `T`, the key type of an id column
+fn strip_option(ty: &Type) -> Type {
+    inner_type(ty, "Option").unwrap_or_else(|| ty.clone())
+}
+
+/// the `T` of a `name<T>`
+fn inner_type(ty: &Type, name: &str) -> Option<Type> {
+    if let Type::Path(p) = ty {
+        if let Some(seg) = p.path.segments.last() {
+            if seg.ident == name {
+                if let PathArguments::AngleBracketed(args) = &seg.arguments {
+                    if let Some(GenericArgument::Type(t)) = args.args.first() {
+                        return Some(t.clone());
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+/// the args of `#[name(a, b = "..")]` as `(a, None)`, `(b, Some(..))`
+fn attr_args(attrs: &[Attribute], name: &str) -> Vec<(String, Option<String>)> {
+    let mut args = vec![];
+    for attr in attrs {
+        if !attr.path.is_ident(name) {
+            continue;
+        }
+        let list = match attr.parse_meta() {
+            Ok(Meta::List(list)) => list,
+            _ => continue,
+        };
+        for nested in list.nested {
+            match nested {
+                NestedMeta::Meta(Meta::Path(p)) => {
+                    if let Some(ident) = p.get_ident() {
+                        args.push((ident.to_string(), None));
+                    }
+                }
+                NestedMeta::Meta(Meta::NameValue(nv)) => {
+                    if let (Some(ident), Lit::Str(s)) = (nv.path.get_ident(), &nv.lit) {
+                        args.push((ident.to_string(), Some(s.value())));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    args
+}
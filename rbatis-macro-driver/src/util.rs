@@ -60,6 +60,27 @@ pub(crate) fn is_fetch(return_source: &str) -> bool {
     return is_select;
 }
 
+//a return of `Page<T>`, not of `CursorPage<T>`
+pub(crate) fn is_page(return_source: &str) -> bool {
+    return_source
+        .split(|c: char| c.is_whitespace() || c == '<' || c == ':')
+        .any(|t| t == "Page")
+}
+
+//the ident of the `&PageRequest` param
+pub(crate) fn find_page_req(target_fn: &ItemFn) -> Option<proc_macro2::TokenStream> {
+    for arg in &target_fn.sig.inputs {
+        if let FnArg::Typed(t) = arg {
+            if t.ty.to_token_stream().to_string().contains("PageRequest") {
+                let name = t.pat.to_token_stream().to_string();
+                let name = name.trim_start_matches("mut ");
+                return Some(proc_macro2::Ident::new(name, proc_macro2::Span::call_site()).to_token_stream());
+            }
+        }
+    }
+    None
+}
+
 pub(crate) fn is_rbatis_ref(ty_stream: &str) -> bool {
     if ty_stream.contains("RbatisExecutor")
         || ty_stream.contains("Rbatis")
@@ -72,3 +93,20 @@ pub(crate) fn is_rbatis_ref(ty_stream: &str) -> bool {
     }
     false
 }
+
+//`BizActivity` -> `biz_activity`
+pub(crate) fn to_snake_name(name: &str) -> String {
+    let mut new_name = String::new();
+    let chs_len = name.len();
+    for (index, x) in name.chars().enumerate() {
+        if x.is_uppercase() {
+            if index != 0 && (index + 1) != chs_len {
+                new_name.push('_');
+            }
+            new_name.push(x.to_ascii_lowercase());
+        } else {
+            new_name.push(x);
+        }
+    }
+    new_name
+}
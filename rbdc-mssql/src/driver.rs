@@ -1,6 +1,7 @@
 use crate::{MssqlConnectOptions, MssqlConnection};
 use futures_core::future::BoxFuture;
 use rbdc::db::{ConnectOptions, Connection, Driver, Placeholder};
+use rbdc::dialect::{quote_with, Dialect, ReturningSyntax, UpsertSyntax};
 use rbdc::Error;
 use tiberius::Config;
 
@@ -35,31 +36,77 @@ impl Driver for MssqlDriver {
     fn default_option(&self) -> Box<dyn ConnectOptions> {
         Box::new(MssqlConnectOptions(Config::new()))
     }
+
+    fn dialect(&self) -> Box<dyn Dialect> {
+        Box::new(MssqlDialect {})
+    }
+}
+
+#[derive(Debug)]
+pub struct MssqlDialect {}
+
+impl Dialect for MssqlDialect {
+    fn quote(&self, ident: &str) -> String {
+        quote_with(ident, '[', ']')
+    }
+
+    /// `offset fetch` needs an `order by`, `order by (select null)` is added if the select has none
+    fn page_limit(&self, offset: u64, limit: u64, ordered: bool) -> String {
+        if ordered {
+            format!("offset {} rows fetch next {} rows only", offset, limit)
+        } else {
+            format!(
+                "order by (select null) offset {} rows fetch next {} rows only",
+                offset, limit
+            )
+        }
+    }
+
+    fn tuple_compare(&self) -> bool {
+        false
+    }
+
+    fn count_over(&self) -> bool {
+        true
+    }
+
+    fn backslash_escapes(&self) -> bool {
+        false
+    }
+
+    fn like_escape(&self) -> &'static str {
+        "escape '\\'"
+    }
+
+    fn bool_literal(&self, v: bool) -> &'static str {
+        if v {
+            "1"
+        } else {
+            "0"
+        }
+    }
+
+    /// a request takes at most 2100 parameters
+    fn max_params(&self) -> usize {
+        2100
+    }
+
+    fn upsert(&self) -> UpsertSyntax {
+        UpsertSyntax::Merge
+    }
+
+    fn returning(&self) -> ReturningSyntax {
+        ReturningSyntax::Output
+    }
+
+    fn begin(&self) -> &str {
+        "begin transaction"
+    }
 }
 
 impl Placeholder for MssqlDriver {
     fn exchange(&self, sql: &str) -> String {
-        let mut last = ' ' as u8;
-        let mut sql_bytes = sql.as_bytes().to_vec();
-        let mut placeholder_idx = 1;
-        let mut index = 0;
-        loop {
-            if index == sql_bytes.len() {
-                break;
-            }
-            let x = sql_bytes[index];
-            if x == '?' as u8 && last != '\\' as u8 {
-                sql_bytes[index] = '@' as u8;
-                sql_bytes.insert(index + 1, 'P' as u8); //P
-                sql_bytes.insert(index + 2, '0' as u8 + placeholder_idx);
-                last = '0' as u8 + placeholder_idx;
-                placeholder_idx += 1;
-            } else {
-                last = x;
-            }
-            index += 1;
-        }
-        String::from_utf8(sql_bytes).unwrap_or_default()
+        rbdc::common::exchange_placeholder(sql, "@P")
     }
 }
 
@@ -205,6 +205,72 @@ impl MySqlConnection {
 }
 
 impl MySqlConnection {
+    /// execute one prepared statement with many argument sets.
+    /// every COM_STMT_EXECUTE is written before the first response is read,
+    /// so the batch costs one round trip. rows returned by the statement are discarded.
+    pub(crate) async fn run_batch(
+        &mut self,
+        sql: &str,
+        arguments: Vec<MySqlArguments>,
+    ) -> Result<Vec<MySqlQueryResult>, Error> {
+        let mut results = Vec::with_capacity(arguments.len());
+        if arguments.is_empty() {
+            return Ok(results);
+        }
+        self.stream.wait_until_ready().await?;
+        let (id, _) = self.get_or_prepare(sql, true).await?;
+
+        for args in &arguments {
+            // https://dev.mysql.com/doc/internals/en/com-stmt-execute.html
+            self.stream.sequence_id = 0;
+            self.stream.write_packet(StatementExecute {
+                statement_id: id,
+                arguments: args,
+            });
+            self.stream.waiting.push_back(Waiting::Result);
+        }
+        self.stream.flush().await?;
+
+        for _ in 0..arguments.len() {
+            let mut done = MySqlQueryResult::default();
+            'response: loop {
+                let packet = self.stream.recv_packet().await?;
+
+                if packet[0] == 0x00 {
+                    let ok = packet.ok()?;
+                    done.rows_affected += ok.affected_rows;
+                    done.last_insert_id = ok.last_insert_id;
+
+                    if ok.status.contains(Status::SERVER_MORE_RESULTS_EXISTS) {
+                        continue;
+                    }
+                    self.stream.waiting.pop_front();
+                    break;
+                }
+
+                // a result set, skip its rows
+                *self.stream.waiting.front_mut().unwrap() = Waiting::Row;
+                self.stream.skip_result_metadata(packet).await?;
+                loop {
+                    let packet = self.stream.recv_packet().await?;
+
+                    if packet[0] == 0xfe && packet.len() < 9 {
+                        let eof = packet.eof(self.stream.capabilities)?;
+
+                        if eof.status.contains(Status::SERVER_MORE_RESULTS_EXISTS) {
+                            *self.stream.waiting.front_mut().unwrap() = Waiting::Result;
+                            break;
+                        }
+                        self.stream.waiting.pop_front();
+                        break 'response;
+                    }
+                }
+            }
+            results.push(done);
+        }
+        Ok(results)
+    }
+
     pub async fn execute(&mut self, sql: &str) -> Result<Option<MySqlRow>, Error> {
         self.fetch_optional(MysqlQuery {
             statement: Either::Left(sql.to_string()),
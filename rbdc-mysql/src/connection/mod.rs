@@ -1,12 +1,12 @@
 use crate::protocol::statement::StmtClose;
 use crate::protocol::text::{Ping, Quit};
-use crate::stmt::MySqlStatementMetadata;
+use crate::stmt::{MySqlArguments, MySqlStatementMetadata};
 use either::Either;
 use futures_core::future::BoxFuture;
 use futures_core::stream::BoxStream;
 use futures_util::{FutureExt, StreamExt, TryStreamExt};
 use rbdc::common::StatementCache;
-use rbdc::db::{Connection, ExecResult, Row};
+use rbdc::db::{Connection, ExecResult, Row, Statement, StatementColumn};
 use rbdc::Error;
 use rbs::Value;
 use std::fmt::{self, Debug, Formatter};
@@ -183,6 +183,65 @@ impl Connection for MySqlConnection {
         })
     }
 
+    fn exec_batch(
+        &mut self,
+        sql: &str,
+        params: Vec<Vec<Value>>,
+    ) -> BoxFuture<'_, Result<Vec<ExecResult>, Error>> {
+        let sql = sql.to_owned();
+        Box::pin(async move {
+            let mut arguments = Vec::with_capacity(params.len());
+            for x in params {
+                arguments.push(MySqlArguments::from(x));
+            }
+            let results = self.run_batch(&sql, arguments).await?;
+            Ok(results
+                .into_iter()
+                .map(|v| ExecResult {
+                    rows_affected: v.rows_affected,
+                    last_insert_id: v.last_insert_id.into(),
+                })
+                .collect())
+        })
+    }
+
+    fn prepare(&mut self, sql: &str) -> BoxFuture<'_, Result<Statement, Error>> {
+        let sql = sql.to_owned();
+        Box::pin(async move {
+            // COM_STMT_PREPARE, the statement is kept in the statement cache
+            let stmt = self.prepare_with(&sql, &[]).await?;
+            Ok(Statement {
+                // the parameter definitions of mysql carry no useful type
+                param_types: vec![String::new(); stmt.metadata.parameters],
+                columns: stmt
+                    .metadata
+                    .columns
+                    .iter()
+                    .map(|c| StatementColumn {
+                        name: c.name.to_string(),
+                        column_type: format!("{:?}", c.type_info.r#type),
+                    })
+                    .collect(),
+                sql,
+            })
+        })
+    }
+
+    fn close_statement(&mut self, sql: &str) -> BoxFuture<'_, Result<(), Error>> {
+        let sql = sql.to_owned();
+        Box::pin(async move {
+            self.stream.wait_until_ready().await?;
+            if let Some((statement_id, _)) = self.cache_statement.remove(&sql) {
+                self.stream
+                    .send_packet(StmtClose {
+                        statement: statement_id,
+                    })
+                    .await?;
+            }
+            Ok(())
+        })
+    }
+
     fn close(&mut self) -> BoxFuture<Result<(), Error>> {
         let c = self.do_close();
         Box::pin(async { c.await })
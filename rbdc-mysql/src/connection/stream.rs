@@ -185,7 +185,7 @@ impl MySqlStream {
         }
     }
 
-    async fn skip_result_metadata(&mut self, mut packet: Packet<Bytes>) -> Result<(), Error> {
+    pub(crate) async fn skip_result_metadata(&mut self, mut packet: Packet<Bytes>) -> Result<(), Error> {
         let num_columns: u64 = packet.get_uint_lenenc(); // column count
 
         for _ in 0..num_columns {
@@ -2,6 +2,7 @@ use crate::connection::MySqlConnection;
 use crate::options::MySqlConnectOptions;
 use futures_core::future::BoxFuture;
 use rbdc::db::{ConnectOptions, Connection, Driver, Placeholder};
+use rbdc::dialect::{quote_with, Dialect};
 use rbdc::Error;
 
 #[derive(Debug)]
@@ -34,6 +35,20 @@ impl Driver for MysqlDriver {
     fn default_option(&self) -> Box<dyn ConnectOptions> {
         Box::new(MySqlConnectOptions::default())
     }
+
+    fn dialect(&self) -> Box<dyn Dialect> {
+        Box::new(MysqlDialect {})
+    }
+}
+
+/// the mysql dialect, the defaults of `Dialect` with backtick quoting
+#[derive(Debug)]
+pub struct MysqlDialect {}
+
+impl Dialect for MysqlDialect {
+    fn quote(&self, ident: &str) -> String {
+        quote_with(ident, '`', '`')
+    }
 }
 
 impl Placeholder for MysqlDriver {
@@ -1 +1,63 @@
+use rbdc::dialect::{merge_with, quote_with, Dialect, UpsertSyntax};
 
+#[derive(Debug)]
+pub struct OracleDialect {}
+
+impl Dialect for OracleDialect {
+    fn quote(&self, ident: &str) -> String {
+        quote_with(ident, '"', '"')
+    }
+
+    /// oracle 12c or later
+    fn page_limit(&self, offset: u64, limit: u64, _ordered: bool) -> String {
+        format!("offset {} rows fetch next {} rows only", offset, limit)
+    }
+
+    fn tuple_compare(&self) -> bool {
+        false
+    }
+
+    fn count_over(&self) -> bool {
+        true
+    }
+
+    fn backslash_escapes(&self) -> bool {
+        false
+    }
+
+    fn like_escape(&self) -> &'static str {
+        "escape '\\'"
+    }
+
+    fn bool_literal(&self, v: bool) -> &'static str {
+        if v {
+            "1"
+        } else {
+            "0"
+        }
+    }
+
+    fn upsert(&self) -> UpsertSyntax {
+        UpsertSyntax::Merge
+    }
+
+    /// the rows are selected from `dual`, oracle has no `values` table and no `;`
+    fn merge_sql(
+        &self,
+        table: &str,
+        columns: &[String],
+        rows: usize,
+        keys: &[String],
+        updates: &[String],
+    ) -> String {
+        let row: Vec<String> = columns.iter().map(|c| format!("? {}", c)).collect();
+        let row = format!("select {} from dual", row.join(","));
+        let source = format!("({}) source", vec![row; rows].join(" union all "));
+        merge_with(table, &source, columns, keys, updates)
+    }
+
+    /// oracle starts transactions implicitly, a no-op block is sent instead
+    fn begin(&self) -> &str {
+        "begin null; end;"
+    }
+}
@@ -191,6 +191,21 @@ impl PgConnection {
         Ok(statement)
     }
 
+    // remove a cached statement and close it on the server
+    pub(crate) async fn close_cached_statement(&mut self, sql: &str) -> Result<(), Error> {
+        self.wait_until_ready().await?;
+        if let Some((id, _)) = self.cache_statement.remove(sql) {
+            self.stream.write(Close::Statement(id));
+            self.write_sync();
+
+            self.stream.flush().await?;
+
+            self.wait_for_close_complete(1).await?;
+            self.recv_ready_for_query().await?;
+        }
+        Ok(())
+    }
+
     async fn run<'e, 'c: 'e, 'q: 'e>(
         &'c mut self,
         query: &'q str,
@@ -332,6 +347,77 @@ impl PgConnection {
 }
 
 impl PgConnection {
+    /// execute one prepared statement with many argument sets in a single pipeline:
+    /// all [Bind]/[Execute] pairs are followed by one [Sync], so the batch costs one round trip.
+    /// the statement is prepared with the parameter types of the first set.
+    pub(crate) async fn run_batch(
+        &mut self,
+        query: &str,
+        arguments: Vec<PgArguments>,
+    ) -> Result<Vec<PgQueryResult>, Error> {
+        let mut results = Vec::with_capacity(arguments.len());
+        if arguments.is_empty() {
+            return Ok(results);
+        }
+        self.wait_until_ready().await?;
+
+        let (statement, metadata) = self
+            .get_or_prepare(query, &arguments[0].types, true, None)
+            .await?;
+
+        let mut arguments = arguments;
+        for args in &mut arguments {
+            args.apply_patches(self, &metadata.parameters).await?;
+        }
+        self.wait_until_ready().await?;
+
+        for args in &arguments {
+            self.stream.write(Bind {
+                portal: None,
+                statement,
+                formats: &[PgValueFormat::Binary],
+                num_params: args.types.len() as i16,
+                params: &*args.buffer,
+                result_formats: &[PgValueFormat::Binary],
+            });
+            self.stream.write(message::Execute {
+                portal: None,
+                limit: 0,
+            });
+        }
+        self.write_sync();
+        self.stream.flush().await?;
+
+        loop {
+            let message = self.stream.recv().await?;
+            match message.format {
+                MessageFormat::BindComplete
+                | MessageFormat::DataRow
+                | MessageFormat::EmptyQueryResponse => {}
+
+                MessageFormat::CommandComplete => {
+                    let cc: CommandComplete = message.decode()?;
+                    results.push(PgQueryResult {
+                        rows_affected: cc.rows_affected(),
+                    });
+                }
+
+                MessageFormat::ReadyForQuery => {
+                    self.handle_ready_for_query(message)?;
+                    break;
+                }
+
+                _ => {
+                    return Err(err_protocol!(
+                        "execute batch: unexpected message: {:?}",
+                        message.format
+                    ));
+                }
+            }
+        }
+        Ok(results)
+    }
+
     pub fn fetch_many(
         &mut self,
         query: PgQuery,
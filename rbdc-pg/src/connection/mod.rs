@@ -1,3 +1,4 @@
+use crate::arguments::PgArguments;
 use crate::driver::PgDriver;
 use crate::message::{
     Close, Message, MessageFormat, Query, ReadyForQuery, Terminate, TransactionStatus,
@@ -10,7 +11,7 @@ use either::Either;
 use futures_core::future::BoxFuture;
 use futures_util::{FutureExt, StreamExt, TryFutureExt, TryStreamExt};
 use rbdc::common::StatementCache;
-use rbdc::db::{Connection, ExecResult, Placeholder, Row};
+use rbdc::db::{Connection, ExecResult, Placeholder, Row, Statement, StatementColumn};
 use rbdc::ext::ustr::UStr;
 use rbdc::io::Decode;
 use rbdc::Error;
@@ -261,4 +262,57 @@ impl Connection for PgConnection {
             });
         })
     }
+
+    fn exec_batch(
+        &mut self,
+        sql: &str,
+        params: Vec<Vec<Value>>,
+    ) -> BoxFuture<'_, Result<Vec<ExecResult>, Error>> {
+        let sql = PgDriver {}.exchange(sql);
+        Box::pin(async move {
+            let mut arguments = Vec::with_capacity(params.len());
+            for x in params {
+                arguments.push(PgArguments::from(x));
+            }
+            let results = self.run_batch(&sql, arguments).await?;
+            Ok(results
+                .into_iter()
+                .map(|v| ExecResult {
+                    rows_affected: v.rows_affected,
+                    last_insert_id: Value::Null,
+                })
+                .collect())
+        })
+    }
+
+    fn prepare(&mut self, sql: &str) -> BoxFuture<'_, Result<Statement, Error>> {
+        let sql = PgDriver {}.exchange(sql);
+        Box::pin(async move {
+            // Parse + Describe, the statement is kept in the statement cache
+            let stmt = self.prepare_with(sql, &[]).await?;
+            Ok(Statement {
+                param_types: stmt
+                    .metadata
+                    .parameters
+                    .iter()
+                    .map(|t| t.name().to_string())
+                    .collect(),
+                columns: stmt
+                    .metadata
+                    .columns
+                    .iter()
+                    .map(|c| StatementColumn {
+                        name: c.name.to_string(),
+                        column_type: c.type_info.name().to_string(),
+                    })
+                    .collect(),
+                sql: stmt.sql,
+            })
+        })
+    }
+
+    fn close_statement(&mut self, sql: &str) -> BoxFuture<'_, Result<(), Error>> {
+        let sql = PgDriver {}.exchange(sql);
+        Box::pin(async move { self.close_cached_statement(&sql).await })
+    }
 }
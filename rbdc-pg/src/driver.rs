@@ -2,6 +2,7 @@ use crate::connection::PgConnection;
 use crate::options::PgConnectOptions;
 use futures_core::future::BoxFuture;
 use rbdc::db::{ConnectOptions, Connection, Driver, Placeholder};
+use rbdc::dialect::{quote_with, Dialect, ReturningSyntax, UpsertSyntax};
 use rbdc::Error;
 
 #[derive(Debug)]
@@ -32,30 +33,48 @@ impl Driver for PgDriver {
     fn default_option(&self) -> Box<dyn ConnectOptions> {
         Box::new(PgConnectOptions::default())
     }
+
+    fn dialect(&self) -> Box<dyn Dialect> {
+        Box::new(PgDialect {})
+    }
+}
+
+#[derive(Debug)]
+pub struct PgDialect {}
+
+impl Dialect for PgDialect {
+    fn quote(&self, ident: &str) -> String {
+        quote_with(ident, '"', '"')
+    }
+
+    fn page_limit(&self, offset: u64, limit: u64, _ordered: bool) -> String {
+        format!("limit {} offset {}", limit, offset)
+    }
+
+    fn count_over(&self) -> bool {
+        true
+    }
+
+    fn backslash_escapes(&self) -> bool {
+        false
+    }
+
+    fn like_escape(&self) -> &'static str {
+        "escape '\\'"
+    }
+
+    fn upsert(&self) -> UpsertSyntax {
+        UpsertSyntax::OnConflict
+    }
+
+    fn returning(&self) -> ReturningSyntax {
+        ReturningSyntax::Returning
+    }
 }
 
 impl Placeholder for PgDriver {
     fn exchange(&self, sql: &str) -> String {
-        let mut last = ' ' as u8;
-        let mut sql_bytes = sql.as_bytes().to_vec();
-        let mut placeholder_idx = 1;
-        let mut index = 0;
-        loop {
-            if index == sql_bytes.len() {
-                break;
-            }
-            let x = sql_bytes[index];
-            if x == '?' as u8 && last != '\\' as u8 {
-                sql_bytes[index] = '$' as u8;
-                sql_bytes.insert(index + 1, '0' as u8 + placeholder_idx);
-                last = '0' as u8 + placeholder_idx;
-                placeholder_idx += 1;
-            } else {
-                last = x;
-            }
-            index += 1;
-        }
-        String::from_utf8(sql_bytes).unwrap_or_default()
+        rbdc::common::exchange_placeholder(sql, "$")
     }
 }
 
@@ -234,6 +234,10 @@ impl Statements {
         self.cached.len()
     }
 
+    fn remove(&mut self, query: &str) {
+        self.cached.remove(query);
+    }
+
     fn clear(&mut self) {
         self.cached.clear();
         self.temp = None;
@@ -43,6 +43,15 @@ pub enum Command {
         persistent: bool,
         tx: flume::Sender<Result<Either<SqliteQueryResult, SqliteRow>, Error>>,
     },
+    ExecuteBatch {
+        query: Box<str>,
+        arguments: Vec<Option<SqliteArguments>>,
+        tx: oneshot::Sender<Result<Vec<SqliteQueryResult>, Error>>,
+    },
+    CloseStatement {
+        query: Box<str>,
+        tx: oneshot::Sender<()>,
+    },
     CreateCollation {
         create_collation:
             Box<dyn FnOnce(&mut ConnectionState) -> Result<(), Error> + Send + Sync + 'static>,
@@ -131,11 +140,24 @@ impl ConnectionWorker {
 
                             update_cached_statements_size(&conn, &shared.cached_statements_size);
                         }
+                        Command::ExecuteBatch {
+                            query,
+                            arguments,
+                            tx,
+                        } => {
+                            tx.send(execute_batch(&mut conn, &query, arguments)).ok();
+                            update_cached_statements_size(&conn, &shared.cached_statements_size);
+                        }
                         Command::CreateCollation { create_collation } => {
                             if let Err(e) = (create_collation)(&mut conn) {
                                 log::warn!("error applying collation in background worker: {}", e);
                             }
                         }
+                        Command::CloseStatement { query, tx } => {
+                            conn.statements.remove(&query);
+                            update_cached_statements_size(&conn, &shared.cached_statements_size);
+                            tx.send(()).ok();
+                        }
                         Command::ClearCache { tx } => {
                             conn.statements.clear();
                             update_cached_statements_size(&conn, &shared.cached_statements_size);
@@ -173,6 +195,14 @@ impl ConnectionWorker {
         .await?
     }
 
+    pub(crate) async fn close_statement(&mut self, query: &str) -> Result<(), Error> {
+        self.oneshot_cmd(|tx| Command::CloseStatement {
+            query: query.into(),
+            tx,
+        })
+        .await
+    }
+
     pub(crate) async fn execute(
         &mut self,
         query: String,
@@ -195,6 +225,23 @@ impl ConnectionWorker {
         Ok(rx)
     }
 
+    /// execute one cached statement for every argument set, in a single round trip to the worker
+    pub(crate) async fn execute_batch(
+        &mut self,
+        query: String,
+        args: Vec<Option<SqliteArguments>>,
+    ) -> Result<Vec<SqliteQueryResult>, Error> {
+        self.oneshot_cmd(|tx| Command::ExecuteBatch {
+            query: query.into(),
+            arguments: args
+                .into_iter()
+                .map(|v| v.map(SqliteArguments::into_static))
+                .collect(),
+            tx,
+        })
+        .await?
+    }
+
     pub(crate) async fn ping(&mut self) -> Result<(), Error> {
         self.oneshot_cmd(|tx| Command::Ping { tx }).await
     }
@@ -293,6 +340,24 @@ fn prepare(conn: &mut ConnectionState, query: &str) -> Result<SqliteStatement, E
     })
 }
 
+fn execute_batch(
+    conn: &mut ConnectionState,
+    query: &str,
+    arguments: Vec<Option<SqliteArguments>>,
+) -> Result<Vec<SqliteQueryResult>, Error> {
+    let mut results = Vec::with_capacity(arguments.len());
+    for args in arguments {
+        let mut result = SqliteQueryResult::default();
+        for res in execute::iter(conn, query, args, true)? {
+            if let Either::Left(done) = res? {
+                result.extend(Some(done));
+            }
+        }
+        results.push(result);
+    }
+    Ok(results)
+}
+
 fn update_cached_statements_size(conn: &ConnectionState, size: &AtomicUsize) {
     size.store(conn.statements.len(), Ordering::Release);
 }
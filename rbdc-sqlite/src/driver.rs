@@ -1,6 +1,7 @@
 use crate::{SqliteConnectOptions, SqliteConnection};
 use futures_core::future::BoxFuture;
 use rbdc::db::{ConnectOptions, Connection, Driver, Placeholder};
+use rbdc::dialect::{quote_with, Dialect, ReturningSyntax, UpsertSyntax};
 use rbdc::Error;
 
 #[derive(Debug)]
@@ -33,6 +34,50 @@ impl Driver for SqliteDriver {
     fn default_option(&self) -> Box<dyn ConnectOptions> {
         Box::new(SqliteConnectOptions::default())
     }
+
+    fn dialect(&self) -> Box<dyn Dialect> {
+        Box::new(SqliteDialect {})
+    }
+}
+
+#[derive(Debug)]
+pub struct SqliteDialect {}
+
+impl Dialect for SqliteDialect {
+    fn quote(&self, ident: &str) -> String {
+        quote_with(ident, '"', '"')
+    }
+
+    fn page_limit(&self, offset: u64, limit: u64, _ordered: bool) -> String {
+        format!("limit {} offset {}", limit, offset)
+    }
+
+    /// `SQLITE_MAX_VARIABLE_NUMBER` of sqlite 3.32 or later, 999 before
+    fn max_params(&self) -> usize {
+        32766
+    }
+
+    /// sqlite 3.25 or later
+    fn count_over(&self) -> bool {
+        true
+    }
+
+    fn backslash_escapes(&self) -> bool {
+        false
+    }
+
+    fn like_escape(&self) -> &'static str {
+        "escape '\\'"
+    }
+
+    fn upsert(&self) -> UpsertSyntax {
+        UpsertSyntax::OnConflict
+    }
+
+    /// sqlite 3.35 or later
+    fn returning(&self) -> ReturningSyntax {
+        ReturningSyntax::Returning
+    }
 }
 
 impl Placeholder for SqliteDriver {
@@ -119,4 +164,59 @@ VALUES ('1', '活动1', NULL, NULL, '1', 1, 1, 'fff', '2019-12-12 00:00:00', 0,
         };
         block_on!(task);
     }
+
+    #[test]
+    fn test_sqlite_exec_batch() {
+        let task = async move {
+            let d = SqliteDriver {};
+            let mut c = d.connect("sqlite://:memory:").await.unwrap();
+            c.exec("create table t (id int primary key, name text)", vec![])
+                .await
+                .unwrap();
+            let results = c
+                .exec_batch(
+                    "insert into t (id, name) values (?, ?)",
+                    vec![
+                        vec![Value::I32(1), Value::String("a".to_string())],
+                        vec![Value::I32(2), Value::String("b".to_string())],
+                    ],
+                )
+                .await
+                .unwrap();
+            assert_eq!(results.len(), 2);
+            assert_eq!(results[1].rows_affected, 1);
+            assert_eq!(results[1].last_insert_id, Value::U64(2));
+            let results = c
+                .exec_batch(
+                    "update t set name = ? where id >= ?",
+                    vec![
+                        vec![Value::String("c".to_string()), Value::I32(1)],
+                        vec![Value::String("d".to_string()), Value::I32(2)],
+                    ],
+                )
+                .await
+                .unwrap();
+            assert_eq!(results[0].rows_affected, 2);
+            assert_eq!(results[1].rows_affected, 1);
+        };
+        block_on!(task);
+    }
+
+    #[test]
+    fn test_sqlite_prepare() {
+        let task = async move {
+            let d = SqliteDriver {};
+            let mut c = d.connect("sqlite://:memory:").await.unwrap();
+            c.exec("create table t (id int primary key, name text)", vec![])
+                .await
+                .unwrap();
+            let stmt = c.prepare("select id, name from t where id = ?").await.unwrap();
+            assert_eq!(stmt.param_types.len(), 1);
+            assert_eq!(stmt.columns.len(), 2);
+            assert_eq!(stmt.columns[1].name, "name");
+            assert!(c.prepare("select * from not_exists").await.is_err());
+            c.close_statement(&stmt.sql).await.unwrap();
+        };
+        block_on!(task);
+    }
 }
@@ -1,11 +1,11 @@
 use crate::query::SqliteQuery;
 use crate::type_info::Type;
-use crate::{SqliteConnectOptions, SqliteConnection, SqliteRow, SqliteQueryResult};
+use crate::{SqliteArguments, SqliteConnectOptions, SqliteConnection, SqliteRow, SqliteQueryResult};
 use either::Either;
 use futures_core::future::BoxFuture;
 use futures_core::stream::BoxStream;
 use futures_util::{StreamExt, TryStreamExt};
-use rbdc::db::{Connection, ExecResult, Row};
+use rbdc::db::{Connection, ExecResult, Row, Statement, StatementColumn};
 use rbdc::error::Error;
 use rbs::Value;
 use std::fmt::Write;
@@ -127,6 +127,59 @@ impl Connection for SqliteConnection {
         })
     }
 
+    fn exec_batch(
+        &mut self,
+        sql: &str,
+        params: Vec<Vec<Value>>,
+    ) -> BoxFuture<'_, Result<Vec<ExecResult>, Error>> {
+        let sql = sql.to_owned();
+        Box::pin(async move {
+            let args = params
+                .into_iter()
+                .map(|v| {
+                    if v.is_empty() {
+                        None
+                    } else {
+                        Some(SqliteArguments::from(v))
+                    }
+                })
+                .collect();
+            let results = self.worker.execute_batch(sql, args).await?;
+            Ok(results
+                .into_iter()
+                .map(|v| ExecResult {
+                    rows_affected: v.rows_affected(),
+                    last_insert_id: Value::U64(v.last_insert_rowid as u64),
+                })
+                .collect())
+        })
+    }
+
+    fn prepare(&mut self, sql: &str) -> BoxFuture<'_, Result<Statement, Error>> {
+        let sql = sql.to_owned();
+        Box::pin(async move {
+            let stmt = self.worker.prepare(&sql).await?;
+            Ok(Statement {
+                sql,
+                // sqlite parameters are untyped
+                param_types: vec![String::new(); stmt.parameters],
+                columns: stmt
+                    .columns
+                    .iter()
+                    .map(|c| StatementColumn {
+                        name: c.name.to_string(),
+                        column_type: c.type_info.to_string(),
+                    })
+                    .collect(),
+            })
+        })
+    }
+
+    fn close_statement(&mut self, sql: &str) -> BoxFuture<'_, Result<(), Error>> {
+        let sql = sql.to_owned();
+        Box::pin(async move { self.worker.close_statement(&sql).await })
+    }
+
     fn close(&mut self) -> BoxFuture<Result<(), Error>> {
         Box::pin(async  { self.do_close().await })
     }
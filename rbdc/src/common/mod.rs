@@ -1,7 +1,9 @@
+mod placeholder;
 mod statement_cache;
 
 pub use crate::types::*;
 
+pub use placeholder::{exchange_placeholder, has_order_by, named_placeholder, strip_order_limit};
 pub use statement_cache::StatementCache;
 use std::fmt::{Debug, Formatter};
 use std::ops::{Deref, DerefMut};
@@ -0,0 +1,432 @@
+use crate::Error;
+use rbs::Value;
+
+/// rewrite `?` placeholders into numbered ones, `prefix` + index starting at 1.
+/// for example `$` for postgres (`$1`) or `@P` for mssql (`@P1`).
+///
+/// `?` inside string literals, quoted identifiers, comments and dollar-quoted bodies is kept,
+/// `'..'` literals are of the standard sql without backslash escapes but postgres `E'..'`.
+/// write `\?` for a literal `?` outside of them, for example the jsonb operator `data \? 'key'`.
+///
+/// ```rust
+/// use rbdc::common::exchange_placeholder;
+/// let sql = exchange_placeholder("select * from t where a = ? and b = '?' and c \\? ?", "$");
+/// assert_eq!(sql, "select * from t where a = $1 and b = '?' and c ? $2");
+/// ```
+pub fn exchange_placeholder(sql: &str, prefix: &str) -> String {
+    let bytes = sql.as_bytes();
+    let mut out = String::with_capacity(sql.len() + 16);
+    let mut placeholder_idx = 1;
+    // bytes before `start` are already copied to `out`
+    let mut start = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if bytes.get(i + 1) == Some(&b'?') => {
+                out.push_str(&sql[start..i]);
+                out.push('?');
+                i += 2;
+                start = i;
+            }
+            b'?' => {
+                out.push_str(&sql[start..i]);
+                out.push_str(prefix);
+                out.push_str(&placeholder_idx.to_string());
+                placeholder_idx += 1;
+                i += 1;
+                start = i;
+            }
+            _ => i = skip_literal(bytes, i, false).unwrap_or(i + 1),
+        }
+    }
+    out.push_str(&sql[start..]);
+    out
+}
+
+/// rewrite `:name` placeholders into `?` and collect their values from `arg`,
+/// a map or anything serialized to a map (`rbs::to_value!(struct)`).
+///
+/// a name may appear many times, an array is expanded into `(?, ?, ?)`.
+/// `::` (postgres casts) and `:` inside string literals, quoted identifiers and comments are kept.
+/// `backslash` if a `\` escapes a quote in `'..'` literals, `Dialect::backslash_escapes()` of mysql.
+///
+/// ```rust
+/// use rbdc::common::named_placeholder;
+/// use rbs::value::map::ValueMap;
+/// use rbs::Value;
+/// let mut arg = ValueMap::new();
+/// arg.insert("a".into(), Value::I32(1));
+/// arg.insert("ids".into(), vec![Value::I32(1), Value::I32(2)].into());
+/// let sql = "select * from t where a = :a and id in :ids and b = :a::int";
+/// let (sql, args) = named_placeholder(sql, &Value::Map(arg), false).unwrap();
+/// assert_eq!(sql, "select * from t where a = ? and id in (?, ?) and b = ?::int");
+/// assert_eq!(args.len(), 4);
+/// ```
+pub fn named_placeholder(sql: &str, arg: &Value, backslash: bool) -> Result<(String, Vec<Value>), Error> {
+    if !matches!(arg, Value::Map(_) | Value::Ext("ValueMap", _)) {
+        return Err(Error::from(format!(
+            "named placeholder arg must be a map or struct, but is {}",
+            arg
+        )));
+    }
+    let bytes = sql.as_bytes();
+    let mut out = String::with_capacity(sql.len() + 16);
+    let mut args = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b':' if bytes.get(i + 1) == Some(&b':') => i += 2,
+            b':' if bytes.get(i + 1).map(|b| b.is_ascii_alphabetic() || *b == b'_') == Some(true) => {
+                let mut end = i + 1;
+                while end < bytes.len() && (bytes[end].is_ascii_alphanumeric() || bytes[end] == b'_') {
+                    end += 1;
+                }
+                let name = &sql[i + 1..end];
+                let v = named_value(arg, name).ok_or_else(|| Error::from(format!("named placeholder :{} not found in arg", name)))?;
+                out.push_str(&sql[start..i]);
+                match v {
+                    Value::Array(arr) => {
+                        if arr.is_empty() {
+                            return Err(Error::from(format!(
+                                "named placeholder :{} is an empty array",
+                                name
+                            )));
+                        }
+                        out.push('(');
+                        for (idx, x) in arr.iter().enumerate() {
+                            if idx > 0 {
+                                out.push_str(", ");
+                            }
+                            out.push('?');
+                            args.push(x.clone());
+                        }
+                        out.push(')');
+                    }
+                    _ => {
+                        out.push('?');
+                        args.push(v.clone());
+                    }
+                }
+                i = end;
+                start = i;
+            }
+            _ => i = skip_literal(bytes, i, backslash).unwrap_or(i + 1),
+        }
+    }
+    out.push_str(&sql[start..]);
+    Ok((out, args))
+}
+
+// a serialized `ValueMap` is `Ext("ValueMap", [[k, v], ..])`
+fn named_value<'a>(arg: &'a Value, name: &str) -> Option<&'a Value> {
+    match arg {
+        Value::Map(m) => m.0.iter().find(|(k, _)| k.as_str() == Some(name)).map(|(_, v)| v),
+        Value::Ext("ValueMap", pairs) => match &**pairs {
+            Value::Array(pairs) => pairs.iter().find_map(|kv| match kv {
+                Value::Array(kv) if kv.len() == 2 && kv[0].as_str() == Some(name) => Some(&kv[1]),
+                _ => None,
+            }),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// a select without its trailing top level `order by`, `limit`, `offset` or `fetch` clauses,
+/// and the number of `?` placeholders removed with them, for the count of a page.
+///
+/// clauses inside parentheses (sub queries, `over (order by ..)`), literals and comments are kept,
+/// `backslash` as of `named_placeholder`.
+///
+/// ```rust
+/// use rbdc::common::strip_order_limit;
+/// let (sql, removed) = strip_order_limit("select * from t where a = ? order by id desc limit ?,?", false);
+/// assert_eq!(sql, "select * from t where a = ?");
+/// assert_eq!(removed, 2);
+/// ```
+pub fn strip_order_limit(sql: &str, backslash: bool) -> (String, usize) {
+    let clause = find_top_level(sql, backslash, |word, next| match word {
+        "order" => is_by(next),
+        "limit" => true,
+        // also a column name, the clause is followed by its count
+        "offset" => next.starts_with(|c: char| c.is_ascii_digit() || c == '?'),
+        "fetch" => {
+            next.starts_with(|c: char| c.is_ascii_digit() || c == '?')
+                || next.starts_with("first")
+                || next.starts_with("next")
+        }
+        _ => false,
+    });
+    match clause {
+        Some(i) => (sql[..i].trim_end().to_string(), count_placeholder(&sql[i..], backslash)),
+        None => (sql.to_string(), 0),
+    }
+}
+
+/// true if the select has a top level `order by`, not one of a sub query, an `over (..)` or a literal,
+/// `backslash` as of `named_placeholder`
+///
+/// ```rust
+/// use rbdc::common::has_order_by;
+/// assert!(has_order_by("select * from t order by id", false));
+/// assert!(!has_order_by("select row_number() over (order by id) from t where a = 'order by'", false));
+/// ```
+pub fn has_order_by(sql: &str, backslash: bool) -> bool {
+    find_top_level(sql, backslash, |word, next| word == "order" && is_by(next)).is_some()
+}
+
+fn is_by(next: &str) -> bool {
+    next.starts_with("by") && !next[2..].starts_with(|c: char| is_ident(c as u8))
+}
+
+// the start of the first word at paren depth 0 outside literals and comments that `matches`,
+// called with the lowercase word and the lowercase sql after it
+fn find_top_level(sql: &str, backslash: bool, mut matches: impl FnMut(&str, &str) -> bool) -> Option<usize> {
+    let bytes = sql.as_bytes();
+    let mut depth = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'(' => depth += 1,
+            b')' => depth -= 1,
+            b if depth == 0 && b.is_ascii_alphabetic() && (i == 0 || !is_ident(bytes[i - 1])) => {
+                let end = bytes[i..]
+                    .iter()
+                    .position(|b| !is_ident(*b))
+                    .map_or(bytes.len(), |n| i + n);
+                let word = sql[i..end].to_lowercase();
+                let next = sql[end..].trim_start().to_lowercase();
+                if matches(&word, &next) {
+                    return Some(i);
+                }
+                i = end;
+                continue;
+            }
+            _ => {
+                if let Some(end) = skip_literal(bytes, i, backslash) {
+                    i = end;
+                    continue;
+                }
+            }
+        }
+        i += 1;
+    }
+    None
+}
+
+// the `?` placeholders of a sql, see `exchange_placeholder`
+fn count_placeholder(sql: &str, backslash: bool) -> usize {
+    let bytes = sql.as_bytes();
+    let mut count = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if bytes.get(i + 1) == Some(&b'?') => i += 2,
+            b'?' => {
+                count += 1;
+                i += 1;
+            }
+            _ => i = skip_literal(bytes, i, backslash).unwrap_or(i + 1),
+        }
+    }
+    count
+}
+
+// the end of a string literal, quoted identifier, comment or dollar-quoted body starting at `i`,
+// `backslash` if every '...' string uses backslash escapes (mysql)
+fn skip_literal(bytes: &[u8], i: usize, backslash: bool) -> Option<usize> {
+    match bytes[i] {
+        b'\'' => {
+            // postgres E'...' strings use backslash escapes
+            let backslash = backslash
+                || i > 0
+                    && (bytes[i - 1] == b'E' || bytes[i - 1] == b'e')
+                    && (i < 2 || !is_ident(bytes[i - 2]));
+            Some(skip_quoted(bytes, i, backslash))
+        }
+        b'"' | b'`' => Some(skip_quoted(bytes, i, false)),
+        b'-' if bytes.get(i + 1) == Some(&b'-') => {
+            Some(match bytes[i..].iter().position(|b| *b == b'\n') {
+                None => bytes.len(),
+                Some(end) => i + end + 1,
+            })
+        }
+        b'/' if bytes.get(i + 1) == Some(&b'*') => Some(skip_block_comment(bytes, i)),
+        b'$' if i == 0 || !is_ident(bytes[i - 1]) => Some(skip_dollar_quoted(bytes, i)),
+        _ => None,
+    }
+}
+
+fn is_ident(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_' || b == b'$' || b >= 0x80
+}
+
+// skip a quoted string or identifier starting at `i`, a doubled quote is an escaped quote
+fn skip_quoted(bytes: &[u8], i: usize, backslash: bool) -> usize {
+    let quote = bytes[i];
+    let mut j = i + 1;
+    while j < bytes.len() {
+        if backslash && bytes[j] == b'\\' {
+            j += 2;
+            continue;
+        }
+        if bytes[j] == quote {
+            if bytes.get(j + 1) == Some(&quote) {
+                j += 2;
+                continue;
+            }
+            return j + 1;
+        }
+        j += 1;
+    }
+    bytes.len()
+}
+
+// block comments nest in postgres
+fn skip_block_comment(bytes: &[u8], i: usize) -> usize {
+    let mut depth = 0;
+    let mut j = i;
+    while j + 1 < bytes.len() {
+        if bytes[j] == b'/' && bytes[j + 1] == b'*' {
+            depth += 1;
+            j += 2;
+        } else if bytes[j] == b'*' && bytes[j + 1] == b'/' {
+            depth -= 1;
+            j += 2;
+            if depth == 0 {
+                return j;
+            }
+        } else {
+            j += 1;
+        }
+    }
+    bytes.len()
+}
+
+// skip `$tag$ ... $tag$`, a `$` that does not start a dollar quote is skipped alone
+fn skip_dollar_quoted(bytes: &[u8], i: usize) -> usize {
+    let mut j = i + 1;
+    while j < bytes.len() && bytes[j] != b'$' {
+        let b = bytes[j];
+        let valid = if j == i + 1 {
+            b.is_ascii_alphabetic() || b == b'_' || b >= 0x80
+        } else {
+            is_ident(b)
+        };
+        if !valid {
+            return i + 1;
+        }
+        j += 1;
+    }
+    if j >= bytes.len() {
+        return i + 1;
+    }
+    let tag = &bytes[i..=j];
+    let body = j + 1;
+    match bytes[body..].windows(tag.len()).position(|w| w == tag) {
+        None => bytes.len(),
+        Some(end) => body + end + tag.len(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::common::{exchange_placeholder, has_order_by, named_placeholder, strip_order_limit};
+    use rbs::value::map::ValueMap;
+    use rbs::Value;
+
+    #[test]
+    fn test_exchange_many() {
+        let sql = "?,".repeat(12);
+        let sql = exchange_placeholder(&sql, "$");
+        assert_eq!(sql, "$1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11,$12,");
+        assert_eq!(exchange_placeholder("a = ? and b = ?", "@P"), "a = @P1 and b = @P2");
+    }
+
+    #[test]
+    fn test_exchange_skip() {
+        let sql = exchange_placeholder(
+            r#"select '?''?', E'\'?', "a?", `b?` -- ?
+/* ? /* ? */ ? */ $$ ? $$, $fn$ ? $fn$, ? from t where data \? 'k' and x = ?"#,
+            "$",
+        );
+        assert_eq!(
+            sql,
+            r#"select '?''?', E'\'?', "a?", `b?` -- ?
+/* ? /* ? */ ? */ $$ ? $$, $fn$ ? $fn$, $1 from t where data ? 'k' and x = $2"#
+        );
+    }
+
+    #[test]
+    fn test_exchange_dollar() {
+        assert_eq!(exchange_placeholder("select a$b$, ? from t", "$"), "select a$b$, $1 from t");
+        assert_eq!(exchange_placeholder("select 'ä?', ?", "$"), "select 'ä?', $1");
+    }
+
+    #[test]
+    fn test_strip_order_limit() {
+        let sql = "select a, row_number() over (order by b) from t where c = 'order by ?' \
+and d in (select d from t2 order by d limit ?) order by a limit ? offset ?";
+        let (stripped, removed) = strip_order_limit(sql, false);
+        assert_eq!(
+            stripped,
+            "select a, row_number() over (order by b) from t where c = 'order by ?' \
+and d in (select d from t2 order by d limit ?)"
+        );
+        assert_eq!(removed, 2);
+        assert_eq!(strip_order_limit("select ordered, limits from t", false), ("select ordered, limits from t".to_string(), 0));
+        assert_eq!(strip_order_limit("select * from t order by x offset 10 rows fetch next 10 rows only", false).0, "select * from t");
+        assert_eq!(strip_order_limit("select * from t order bys", false).0, "select * from t order bys");
+        // a column named offset or fetch
+        let (stripped, removed) = strip_order_limit("select * from t where offset > ? and fetch = ? offset ? rows", false);
+        assert_eq!(stripped, "select * from t where offset > ? and fetch = ?");
+        assert_eq!(removed, 1);
+        assert_eq!(strip_order_limit("select * from t fetch first 10 rows only", false).0, "select * from t");
+    }
+
+    #[test]
+    fn test_has_order_by() {
+        assert!(!has_order_by("select * from t where a = 'it\\'s' order by id", false));
+        assert!(has_order_by("select * from t where a = 'it\\'s' order by id", true));
+        assert!(has_order_by("select * from t where a = 'order by' order by id", false));
+        assert!(!has_order_by("select * from t where id in (select id from t2 order by id)", false));
+        assert!(!has_order_by("select a, row_number() over (order by b) from t -- order by a", false));
+    }
+
+    #[test]
+    fn test_named_placeholder() {
+        let mut arg = ValueMap::new();
+        arg.insert("a".into(), Value::I32(1));
+        arg.insert("name".into(), "n".into());
+        arg.insert("ids".into(), vec![Value::I32(1), Value::I32(2), Value::I32(3)].into());
+        let arg = Value::Map(arg);
+        let (sql, args) = named_placeholder(
+            "select ':a', \"b:a\" from t where a = :a and name = :name -- :x\n and id in :ids or b = :a",
+            &arg,
+            false,
+        )
+        .unwrap();
+        assert_eq!(
+            sql,
+            "select ':a', \"b:a\" from t where a = ? and name = ? -- :x\n and id in (?, ?, ?) or b = ?"
+        );
+        assert_eq!(
+            args,
+            vec![
+                Value::I32(1),
+                Value::String("n".to_string()),
+                Value::I32(1),
+                Value::I32(2),
+                Value::I32(3),
+                Value::I32(1)
+            ]
+        );
+        assert!(named_placeholder("select :b", &arg, false).is_err());
+        assert!(named_placeholder("select :a", &Value::I32(1), false).is_err());
+        // a mysql `\'` does not end the literal
+        let (sql, args) = named_placeholder("select 'it\\'s :a' from t where a = :a", &arg, true).unwrap();
+        assert_eq!(sql, "select 'it\\'s :a' from t where a = ?");
+        assert_eq!(args.len(), 1);
+    }
+}
@@ -38,6 +38,11 @@ impl<T> StatementCache<T> {
         lru_item
     }
 
+    /// Removes the statement of the given key, returning it if it was cached.
+    pub fn remove(&mut self, k: &str) -> Option<T> {
+        self.inner.remove(k)
+    }
+
     /// The number of statements in the cache.
     pub fn len(&self) -> usize {
         self.inner.len()
@@ -1,3 +1,4 @@
+use crate::dialect::{DefaultDialect, Dialect};
 use crate::Error;
 use futures_core::future::BoxFuture;
 use rbs::value::map::ValueMap;
@@ -20,6 +21,11 @@ pub trait Driver: Debug + Sync + Send {
 
     /// make an default option
     fn default_option(&self) -> Box<dyn ConnectOptions>;
+
+    /// the sql syntax of this database, default `DefaultDialect`
+    fn dialect(&self) -> Box<dyn Dialect> {
+        Box::new(DefaultDialect {})
+    }
 }
 
 #[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
@@ -76,6 +82,40 @@ pub trait Connection: Send {
     /// Execute a query that is expected to update some rows.
     fn exec(&mut self, sql: &str, params: Vec<Value>) -> BoxFuture<Result<ExecResult, Error>>;
 
+    /// Execute one statement with many parameter sets, returning one `ExecResult` per set.
+    ///
+    /// drivers override this to send all sets in a single pipeline,
+    /// the default runs `exec` once per set.
+    fn exec_batch(
+        &mut self,
+        sql: &str,
+        params: Vec<Vec<Value>>,
+    ) -> BoxFuture<'_, Result<Vec<ExecResult>, Error>> {
+        let sql = sql.to_owned();
+        Box::pin(async move {
+            let mut results = Vec::with_capacity(params.len());
+            for args in params {
+                results.push(self.exec(&sql, args).await?);
+            }
+            Ok(results)
+        })
+    }
+
+    /// Prepare a statement and describe its parameters and columns.
+    ///
+    /// the driver keeps the statement in its statement cache,
+    /// so `exec`/`get_rows` with the same sql skip the parse step until `close_statement`.
+    fn prepare(&mut self, sql: &str) -> BoxFuture<'_, Result<Statement, Error>> {
+        let _ = sql;
+        Box::pin(async { Err(Error::from("prepare is not supported by this driver")) })
+    }
+
+    /// Close a statement prepared by `prepare` (or cached by `exec`) and free it on the server
+    fn close_statement(&mut self, sql: &str) -> BoxFuture<'_, Result<(), Error>> {
+        let _ = sql;
+        Box::pin(async { Ok(()) })
+    }
+
     /// close connection
     fn close(&mut self) -> BoxFuture<Result<(), Error>>;
 
@@ -83,6 +123,22 @@ pub trait Connection: Send {
     fn ping(&mut self) -> BoxFuture<Result<(), Error>>;
 }
 
+/// A statement prepared by `Connection::prepare`
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct Statement {
+    pub sql: String,
+    /// the type name of every parameter, empty names if the database does not describe them
+    pub param_types: Vec<String>,
+    pub columns: Vec<StatementColumn>,
+}
+
+/// A result column of a `Statement`
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct StatementColumn {
+    pub name: String,
+    pub column_type: String,
+}
+
 /// Result set from executing a query against a statement
 pub trait Row: 'static + Send + Debug {
     /// get meta data about this result set
@@ -147,6 +203,8 @@ impl dyn ConnectOptions {
     }
 }
 
+/// rewrite the `?` placeholders of rbatis sql into the driver's syntax,
+/// see `rbdc::common::exchange_placeholder`
 pub trait Placeholder {
     fn exchange(&self, sql: &str) -> String;
 }
@@ -0,0 +1,243 @@
+use std::fmt::Debug;
+
+/// how a database inserts a row or updates it when it already exists
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpsertSyntax {
+    /// `INSERT ... ON DUPLICATE KEY UPDATE` (mysql)
+    OnDuplicateKey,
+    /// `INSERT ... ON CONFLICT (..) DO UPDATE` (postgres, sqlite)
+    OnConflict,
+    /// `MERGE INTO ...` (mssql, oracle)
+    Merge,
+}
+
+/// how a database returns generated values of an insert
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReturningSyntax {
+    /// not supported, use `last_insert_id` of the `ExecResult` (mysql)
+    None,
+    /// `INSERT ... RETURNING ..` (postgres, sqlite)
+    Returning,
+    /// `INSERT ... OUTPUT INSERTED.. VALUES ..` (mssql)
+    Output,
+}
+
+/// the sql syntax of a database, provided by every driver with `Driver::dialect()`.
+///
+/// the defaults are the mysql syntax, drivers override what differs.
+pub trait Dialect: Debug + Send + Sync {
+    /// quote an identifier, `a.b` is quoted as two identifiers.
+    /// the default keeps identifiers unquoted
+    fn quote(&self, ident: &str) -> String {
+        ident.to_string()
+    }
+
+    /// the clause appended to a select to fetch one page, `ordered` is true if the select has an `order by`.
+    ///
+    /// default `limit {offset},{limit}`
+    fn page_limit(&self, offset: u64, limit: u64, ordered: bool) -> String {
+        let _ = ordered;
+        format!("limit {},{}", offset, limit)
+    }
+
+    /// append the page clause to a select, `ordered` if it has a top level `order by`
+    fn page_sql(&self, sql: &str, offset: u64, limit: u64) -> String {
+        let ordered = crate::common::has_order_by(sql, self.backslash_escapes());
+        format!("{} {}", sql, self.page_limit(offset, limit, ordered))
+    }
+
+    /// true if a `\` escapes a quote in `'..'` string literals, else only a doubled `''` does.
+    ///
+    /// default true of mysql, false is the standard sql of postgres, sqlite, mssql and oracle
+    fn backslash_escapes(&self) -> bool {
+        true
+    }
+
+    /// the most `?` parameters of one statement, batches are split to stay under it.
+    ///
+    /// default 65535 of mysql and postgres
+    fn max_params(&self) -> usize {
+        65535
+    }
+
+    /// true if rows compare as tuples, `(a, b) > (?, ?)`,
+    /// else the keyset of a cursor page is expanded to `a > ? or (a = ? and b > ?)`
+    fn tuple_compare(&self) -> bool {
+        true
+    }
+
+    /// true if `count(*) over()` window functions are supported, a page then counts its total in the same select.
+    ///
+    /// default false of mysql before 8.0
+    fn count_over(&self) -> bool {
+        false
+    }
+
+    /// the clause after a `like ?` whose value escapes `%`, `_` and `\` with a `\`.
+    ///
+    /// default `escape '\\'` of mysql, whose string literals escape a backslash too
+    fn like_escape(&self) -> &'static str {
+        "escape '\\\\'"
+    }
+
+    /// the literal of a boolean, default `true`/`false`
+    fn bool_literal(&self, v: bool) -> &'static str {
+        if v {
+            "true"
+        } else {
+            "false"
+        }
+    }
+
+    /// default `UpsertSyntax::OnDuplicateKey`
+    fn upsert(&self) -> UpsertSyntax {
+        UpsertSyntax::OnDuplicateKey
+    }
+
+    /// the `merge` of an upsert of `UpsertSyntax::Merge`, every name is quoted.
+    ///
+    /// `rows` rows of `?` placeholders of `columns` are merged on the `keys`,
+    /// matched rows update the `updates` columns, none updates nothing.
+    /// default the mssql syntax `merge into t target using (values (?,?)) source (a,b) on (..) ..;`
+    fn merge_sql(
+        &self,
+        table: &str,
+        columns: &[String],
+        rows: usize,
+        keys: &[String],
+        updates: &[String],
+    ) -> String {
+        let row = format!("({})", vec!["?"; columns.len()].join(","));
+        let source = format!(
+            "(values {}) source ({})",
+            vec![row; rows].join(","),
+            columns.join(",")
+        );
+        merge_with(table, &source, columns, keys, updates) + ";"
+    }
+
+    /// default `ReturningSyntax::None`
+    fn returning(&self) -> ReturningSyntax {
+        ReturningSyntax::None
+    }
+
+    /// the statement starting a transaction, default `begin`
+    fn begin(&self) -> &str {
+        "begin"
+    }
+
+    /// default `commit`
+    fn commit(&self) -> &str {
+        "commit"
+    }
+
+    /// default `rollback`
+    fn rollback(&self) -> &str {
+        "rollback"
+    }
+}
+
+/// the dialect of drivers that do not provide one, see the defaults of `Dialect`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultDialect {}
+
+impl Dialect for DefaultDialect {}
+
+/// `merge into {table} target using {source} on (..) when matched .. when not matched ..`
+pub fn merge_with(
+    table: &str,
+    source: &str,
+    columns: &[String],
+    keys: &[String],
+    updates: &[String],
+) -> String {
+    let on: Vec<String> = keys
+        .iter()
+        .map(|k| format!("target.{} = source.{}", k, k))
+        .collect();
+    let mut sql = format!(
+        "merge into {} target using {} on ({})",
+        table,
+        source,
+        on.join(" and ")
+    );
+    if !updates.is_empty() {
+        let set: Vec<String> = updates
+            .iter()
+            .map(|u| format!("target.{} = source.{}", u, u))
+            .collect();
+        sql.push_str(" when matched then update set ");
+        sql.push_str(&set.join(","));
+    }
+    let values: Vec<String> = columns.iter().map(|c| format!("source.{}", c)).collect();
+    sql.push_str(&format!(
+        " when not matched then insert ({}) values ({})",
+        columns.join(","),
+        values.join(",")
+    ));
+    sql
+}
+
+/// quote every part of `a.b` with `open` and `close`, doubling `close` inside a part
+pub fn quote_with(ident: &str, open: char, close: char) -> String {
+    let mut s = String::with_capacity(ident.len() + 4);
+    for (i, part) in ident.split('.').enumerate() {
+        if i > 0 {
+            s.push('.');
+        }
+        s.push(open);
+        for c in part.chars() {
+            if c == close {
+                s.push(close);
+            }
+            s.push(c);
+        }
+        s.push(close);
+    }
+    s
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dialect::{quote_with, DefaultDialect, Dialect};
+
+    #[derive(Debug)]
+    struct FetchDialect {}
+
+    impl Dialect for FetchDialect {
+        fn page_limit(&self, offset: u64, limit: u64, ordered: bool) -> String {
+            let order = if ordered { "" } else { "order by (select null) " };
+            format!("{}offset {} rows fetch next {} rows only", order, offset, limit)
+        }
+    }
+
+    #[test]
+    fn test_default_dialect() {
+        let d = DefaultDialect {};
+        assert_eq!(d.quote("id"), "id");
+        assert_eq!(d.page_sql("select * from t", 20, 10), "select * from t limit 20,10");
+        assert!(d.tuple_compare());
+        assert_eq!(quote_with("public.t\"a", '"', '"'), "\"public\".\"t\"\"a\"");
+        assert_eq!(quote_with("t]", '[', ']'), "[t]]]");
+        let columns = vec!["id".to_string(), "name".to_string()];
+        assert_eq!(
+            d.merge_sql("t", &columns, 2, &columns[..1], &columns[1..]),
+            "merge into t target using (values (?,?),(?,?)) source (id,name) on (target.id = source.id) \
+when matched then update set target.name = source.name \
+when not matched then insert (id,name) values (source.id,source.name);"
+        );
+    }
+    #[test]
+    fn test_page_sql_ordered() {
+        let d = FetchDialect {};
+        assert_eq!(
+            d.page_sql("select * from t order by id", 0, 10),
+            "select * from t order by id offset 0 rows fetch next 10 rows only"
+        );
+        assert_eq!(
+            d.page_sql("select * from t where id in (select top 1 id from t2 order by id)", 0, 10),
+            "select * from t where id in (select top 1 id from t2 order by id) order by (select null) offset 0 rows fetch next 10 rows only"
+        );
+        assert!(d.page_sql("select * from t where a = 'order by'", 0, 10).contains("(select null)"));
+    }
+}
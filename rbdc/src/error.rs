@@ -96,12 +96,6 @@ impl From<rbs::Error> for Error {
     }
 }
 
-impl From<mobc::Error<Error>> for Error {
-    fn from(arg: mobc::Error<Error>) -> Self {
-        Error::from(arg.to_string())
-    }
-}
-
 // Format an error message as a `Protocol` error
 #[macro_export]
 macro_rules! err_protocol {
@@ -1,6 +1,7 @@
 pub mod common;
 pub use common::*;
 pub mod db;
+pub mod dialect;
 pub mod error;
 #[macro_use]
 pub mod ext;
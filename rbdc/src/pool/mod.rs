@@ -1,2 +1,4 @@
+pub mod options;
 pub mod pool;
+pub use options::*;
 pub use pool::*;
@@ -0,0 +1,245 @@
+use crate::common::DebugFn;
+use crate::db::Connection;
+use crate::Error;
+use futures_core::future::BoxFuture;
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// an async callback run on a pooled connection, returning an error rejects the connection
+pub type ConnectionHook = Arc<
+    DebugFn<
+        dyn for<'a> Fn(&'a mut dyn Connection) -> BoxFuture<'a, Result<(), Error>>
+            + Send
+            + Sync,
+    >,
+>;
+
+/// provides the url for new connections, for example to rotate database passwords
+/// from a secret store without restarting.
+///
+/// it is called for every new physical connection, so implementations should cache
+/// the credentials.
+pub trait CredentialProvider: Debug + Send + Sync {
+    /// the url with current credentials
+    fn url(&self) -> BoxFuture<'_, Result<String, Error>>;
+}
+
+/// how the pool checks that a connection is still alive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthCheck {
+    /// never check connections
+    None,
+    /// ping the connection every time before it is handed out
+    OnBorrow,
+    /// ping idle connections in the background at this interval
+    Periodic(Duration),
+}
+
+/// Pool options
+///
+/// for example:
+/// ```rust
+/// use std::time::Duration;
+/// use rbdc::pool::PoolOptions;
+/// let opt = PoolOptions::new()
+///     .max_connections(20)
+///     .min_connections(2)
+///     .acquire_timeout(Duration::from_secs(5));
+/// ```
+#[derive(Debug, Clone)]
+pub struct PoolOptions {
+    /// maximum number of open connections, default 10
+    pub max_connections: u32,
+    /// number of connections the pool keeps open, opened at link time, default 0
+    pub min_connections: u32,
+    /// maximum time to wait for a connection, default 30s
+    pub acquire_timeout: Duration,
+    /// idle connections above `min_connections` are closed after this time, default 10min
+    pub idle_timeout: Option<Duration>,
+    /// connections are closed after this time, default 30min
+    pub max_lifetime: Option<Duration>,
+    /// a random duration up to this value is subtracted from `max_lifetime` of every connection,
+    /// so that connections do not expire all at once. default 0
+    pub max_lifetime_jitter: Duration,
+    /// default `HealthCheck::OnBorrow`
+    pub health_check: HealthCheck,
+    /// run on every new physical connection, an error closes it and fails the acquire
+    pub after_connect: Option<ConnectionHook>,
+    /// run on an idle connection before it is handed out, an error closes it and
+    /// the pool tries another one
+    pub before_acquire: Option<ConnectionHook>,
+    /// run on a connection when it returns to the pool, an error closes it
+    pub after_release: Option<ConnectionHook>,
+    /// consulted for every new connection instead of the linked url
+    pub credential_provider: Option<Arc<dyn CredentialProvider>>,
+}
+
+impl Default for PoolOptions {
+    fn default() -> Self {
+        Self {
+            max_connections: 10,
+            min_connections: 0,
+            acquire_timeout: Duration::from_secs(30),
+            idle_timeout: Some(Duration::from_secs(10 * 60)),
+            max_lifetime: Some(Duration::from_secs(30 * 60)),
+            max_lifetime_jitter: Duration::from_secs(0),
+            health_check: HealthCheck::OnBorrow,
+            after_connect: None,
+            before_acquire: None,
+            after_release: None,
+            credential_provider: None,
+        }
+    }
+}
+
+impl PoolOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn max_connections(mut self, arg: u32) -> Self {
+        self.max_connections = arg;
+        self
+    }
+
+    pub fn min_connections(mut self, arg: u32) -> Self {
+        self.min_connections = arg;
+        self
+    }
+
+    pub fn acquire_timeout(mut self, arg: Duration) -> Self {
+        self.acquire_timeout = arg;
+        self
+    }
+
+    pub fn idle_timeout(mut self, arg: Option<Duration>) -> Self {
+        self.idle_timeout = arg;
+        self
+    }
+
+    pub fn max_lifetime(mut self, arg: Option<Duration>) -> Self {
+        self.max_lifetime = arg;
+        self
+    }
+
+    pub fn max_lifetime_jitter(mut self, arg: Duration) -> Self {
+        self.max_lifetime_jitter = arg;
+        self
+    }
+
+    pub fn health_check(mut self, arg: HealthCheck) -> Self {
+        self.health_check = arg;
+        self
+    }
+
+    /// set the `after_connect` hook
+    ///
+    /// for example:
+    /// ```rust
+    /// use rbdc::pool::PoolOptions;
+    /// let opt = PoolOptions::new().after_connect(|conn| {
+    ///     Box::pin(async move {
+    ///         conn.exec("PRAGMA foreign_keys = ON", vec![]).await?;
+    ///         Ok(())
+    ///     })
+    /// });
+    /// ```
+    pub fn after_connect<F>(mut self, f: F) -> Self
+    where
+        F: for<'a> Fn(&'a mut dyn Connection) -> BoxFuture<'a, Result<(), Error>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.after_connect = Some(Arc::new(DebugFn(f)));
+        self
+    }
+
+    /// set the `before_acquire` hook
+    pub fn before_acquire<F>(mut self, f: F) -> Self
+    where
+        F: for<'a> Fn(&'a mut dyn Connection) -> BoxFuture<'a, Result<(), Error>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.before_acquire = Some(Arc::new(DebugFn(f)));
+        self
+    }
+
+    /// set the `after_release` hook, for example to reset session state with `DISCARD ALL`
+    pub fn after_release<F>(mut self, f: F) -> Self
+    where
+        F: for<'a> Fn(&'a mut dyn Connection) -> BoxFuture<'a, Result<(), Error>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.after_release = Some(Arc::new(DebugFn(f)));
+        self
+    }
+
+    pub fn credential_provider(mut self, arg: Arc<dyn CredentialProvider>) -> Self {
+        self.credential_provider = Some(arg);
+        self
+    }
+
+    /// read pool options from the url query and return the url without them.
+    ///
+    /// durations are in seconds, `0` disables `idle_timeout`/`max_lifetime`.
+    /// `health_check` is `none`, `on_borrow` or the interval of periodic checks in seconds.
+    ///
+    /// for example:
+    /// ```rust
+    /// use rbdc::pool::PoolOptions;
+    /// let mut opt = PoolOptions::new();
+    /// let url = opt.set_uri("postgres://localhost/db?sslmode=disable&max_connections=20").unwrap();
+    /// assert_eq!(url, "postgres://localhost/db?sslmode=disable");
+    /// assert_eq!(opt.max_connections, 20);
+    /// ```
+    pub fn set_uri(&mut self, url: &str) -> Result<String, Error> {
+        let (base, query) = match url.find('?') {
+            None => return Ok(url.to_string()),
+            Some(idx) => (&url[..idx], &url[idx + 1..]),
+        };
+        let mut remain = vec![];
+        for pair in query.split('&') {
+            let (k, v) = match pair.find('=') {
+                None => (pair, ""),
+                Some(idx) => (&pair[..idx], &pair[idx + 1..]),
+            };
+            match k {
+                "max_connections" => self.max_connections = v.parse()?,
+                "min_connections" => self.min_connections = v.parse()?,
+                "acquire_timeout" => self.acquire_timeout = Duration::from_secs(v.parse()?),
+                "idle_timeout" => self.idle_timeout = secs_option(v.parse()?),
+                "max_lifetime" => self.max_lifetime = secs_option(v.parse()?),
+                "max_lifetime_jitter" => {
+                    self.max_lifetime_jitter = Duration::from_secs(v.parse()?)
+                }
+                "health_check" => {
+                    self.health_check = match v {
+                        "none" => HealthCheck::None,
+                        "on_borrow" => HealthCheck::OnBorrow,
+                        _ => HealthCheck::Periodic(Duration::from_secs(v.parse()?)),
+                    }
+                }
+                _ => remain.push(pair),
+            }
+        }
+        if remain.is_empty() {
+            Ok(base.to_string())
+        } else {
+            Ok(format!("{}?{}", base, remain.join("&")))
+        }
+    }
+}
+
+fn secs_option(secs: u64) -> Option<Duration> {
+    if secs == 0 {
+        None
+    } else {
+        Some(Duration::from_secs(secs))
+    }
+}
@@ -42,3 +42,16 @@ macro_rules! to_value {
         $crate::to_value($arg).unwrap_or_default()
     };
 }
+
+/// a `Value::Map` of the `key: value` pairs, `value!{"status": 1, "id": ["1", "2"]}`
+#[macro_export]
+macro_rules! value {
+    {} => {
+        $crate::Value::Map($crate::value::map::ValueMap::new())
+    };
+    {$($k:tt: $v:expr),+ $(,)?} => {{
+        let mut m = $crate::value::map::ValueMap::new();
+        $(m.insert($crate::to_value!($k), $crate::to_value!($v));)+
+        $crate::Value::Map(m)
+    }};
+}
@@ -12,6 +12,484 @@ macro_rules! crud {
         $crate::impl_update!($table {},$table_name);
         $crate::impl_delete!($table {},$table_name);
     };
+    ($table:ty{},$table_name:expr,[$($id:expr),+ $(,)?]) => {
+        $crate::impl_insert!($table {},$table_name,[$($id),+]);
+        $crate::impl_select!($table {},$table_name,[$($id),+]);
+        $crate::impl_update!($table {},$table_name,[$($id),+]);
+        $crate::impl_delete!($table {},$table_name,[$($id),+]);
+    };
+}
+
+/// quote the keys of a map, or of every map in an array, with `dialect.quote()`
+pub fn quote_keys(dialect: &dyn rbdc::dialect::Dialect, v: rbs::Value) -> rbs::Value {
+    match v {
+        rbs::Value::Map(m) => {
+            let mut quoted = rbs::value::map::ValueMap::with_capacity(m.len());
+            for (k, v) in m {
+                let k = match k {
+                    rbs::Value::String(k) => rbs::Value::String(dialect.quote(&k)),
+                    k => k,
+                };
+                quoted.insert(k, v);
+            }
+            rbs::Value::Map(quoted)
+        }
+        rbs::Value::Array(arr) => {
+            rbs::Value::Array(arr.into_iter().map(|v| quote_keys(dialect, v)).collect())
+        }
+        v => v,
+    }
+}
+
+/// the values of a key, `ids` are the key columns, a key of more columns is a tuple
+pub fn key_values(ids: &[&str], key: rbs::Value) -> Result<Vec<rbs::Value>, rbdc::Error> {
+    if ids.is_empty() {
+        return Err(rbdc::Error::from("[rbatis] the table has no id column!"));
+    }
+    if ids.len() == 1 {
+        return Ok(vec![key]);
+    }
+    match key {
+        rbs::Value::Array(arr) if arr.len() == ids.len() => Ok(arr),
+        key => Err(rbdc::Error::from(format!(
+            "[rbatis] the key of ({}) must be a tuple of {} values, but is {}",
+            ids.join(","),
+            ids.len(),
+            key
+        ))),
+    }
+}
+
+/// `where id = ?`, `where id in (?,?)`, `where a = ? and b = ?`
+/// or `where (a = ? and b = ?) or (a = ? and b = ?)` of the quoted `ids` and the `keys` values
+pub fn key_where(
+    dialect: &dyn rbdc::dialect::Dialect,
+    ids: &[&str],
+    keys: Vec<Vec<rbs::Value>>,
+) -> (String, Vec<rbs::Value>) {
+    let ids: Vec<String> = ids.iter().map(|id| dialect.quote(id)).collect();
+    let mut sql = String::from("where ");
+    if ids.len() == 1 && keys.len() > 1 {
+        sql.push_str(&ids[0]);
+        sql.push_str(" in (");
+        for i in 0..keys.len() {
+            if i > 0 {
+                sql.push(',');
+            }
+            sql.push('?');
+        }
+        sql.push(')');
+    } else {
+        for i in 0..keys.len() {
+            if i > 0 {
+                sql.push_str(" or ");
+            }
+            if keys.len() > 1 {
+                sql.push('(');
+            }
+            for (j, id) in ids.iter().enumerate() {
+                if j > 0 {
+                    sql.push_str(" and ");
+                }
+                sql.push_str(id);
+                sql.push_str(" = ?");
+            }
+            if keys.len() > 1 {
+                sql.push(')');
+            }
+        }
+    }
+    (sql, keys.into_iter().flatten().collect())
+}
+
+/// the rows of a statement with `params_per_row` parameters a row, under `dialect.max_params()`
+pub fn chunk_size_of(dialect: &dyn rbdc::dialect::Dialect, params_per_row: usize) -> usize {
+    (dialect.max_params() / params_per_row.max(1)).max(1)
+}
+
+/// sum the `rows_affected` of split statements, keeping the `last_insert_id` of the first
+pub fn merge_result(result: Option<rbdc::db::ExecResult>, next: rbdc::db::ExecResult) -> rbdc::db::ExecResult {
+    match result {
+        None => next,
+        Some(mut result) => {
+            result.rows_affected += next.rows_affected;
+            result
+        }
+    }
+}
+
+/// a field of a partial update, tells "leave the column alone" from "set it to null".
+///
+/// `Unset` is skipped by every update, even `update_by_column_with_nulls`, and is null to an insert.
+/// `Null` is written as null even by `update_by_column`:
+/// ```rust
+/// #[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+/// pub struct BizActivityPatch {
+///     pub id: Option<String>,
+///     #[serde(default)]
+///     pub remark: rbatis::Patch<String>,
+/// }
+/// let clear = BizActivityPatch { id: Some("1".to_string()), remark: rbatis::Patch::Null };
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Patch<T> {
+    #[default]
+    Unset,
+    Null,
+    Value(T),
+}
+
+impl<T> Patch<T> {
+    pub fn is_unset(&self) -> bool {
+        matches!(self, Patch::Unset)
+    }
+}
+
+impl<T> From<Option<T>> for Patch<T> {
+    fn from(v: Option<T>) -> Self {
+        match v {
+            None => Patch::Null,
+            Some(v) => Patch::Value(v),
+        }
+    }
+}
+
+impl<T: serde::Serialize> serde::Serialize for Patch<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            // `Value::Ext("Unset", Null)`, skipped by the updates with nulls too
+            Patch::Unset => serializer.serialize_newtype_struct("Unset", &()),
+            // `Value::Ext("Null", Null)`, not skipped as null by the updates
+            Patch::Null => serializer.serialize_newtype_struct("Null", &()),
+            Patch::Value(v) => serializer.serialize_some(v),
+        }
+    }
+}
+
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for Patch<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Option::<T>::deserialize(deserializer)?.into())
+    }
+}
+
+/// the value an update writes, `None` skips the column.
+///
+/// null is skipped unless `with_nulls`, `Patch::Null` is always written as null and `Patch::Unset` never
+pub fn update_value(v: &rbs::Value, with_nulls: bool) -> Option<rbs::Value> {
+    match v {
+        rbs::Value::Ext("Unset", _) => None,
+        rbs::Value::Ext("Null", _) => Some(rbs::Value::Null),
+        rbs::Value::Null if !with_nulls => None,
+        v => Some(v.clone()),
+    }
+}
+
+/// the `Patch::Null` and `Patch::Unset` markers of args as null, a driver binds no `Value::Ext("Null", ..)`
+pub fn plain_args(args: &mut [rbs::Value]) {
+    for v in args.iter_mut() {
+        if let rbs::Value::Ext("Null" | "Unset", _) = v {
+            *v = rbs::Value::Null;
+        }
+    }
+}
+
+/// the columns of a table map an update writes, see `update_value`
+pub fn update_columns(table: rbs::Value, with_nulls: bool) -> rbs::Value {
+    match table {
+        rbs::Value::Map(m) => {
+            let mut columns = rbs::value::map::ValueMap::with_capacity(m.len());
+            for (k, v) in m {
+                if let Some(v) = update_value(&v, with_nulls) {
+                    columns.insert(k, v);
+                }
+            }
+            rbs::Value::Map(columns)
+        }
+        table => table,
+    }
+}
+
+/// a row of `update_batch_sql`, the `key` values of the key columns and the columns to set
+#[derive(Debug, Clone, Default)]
+pub struct UpdateRow {
+    pub key: Vec<rbs::Value>,
+    pub sets: Vec<(String, rbs::Value)>,
+}
+
+/// one statement updating every row by its key, a column not set by a row keeps its value:
+///
+/// `update t set a = case when k = ? then ? when k = ? then ? else a end where k in (?,?)`
+pub fn update_batch_sql(
+    dialect: &dyn rbdc::dialect::Dialect,
+    table: &str,
+    keys: &[&str],
+    rows: Vec<UpdateRow>,
+) -> Result<(String, Vec<rbs::Value>), rbdc::Error> {
+    let mut columns: Vec<&str> = vec![];
+    for row in &rows {
+        for (c, _) in &row.sets {
+            if !columns.contains(&c.as_str()) {
+                columns.push(c);
+            }
+        }
+    }
+    if columns.is_empty() {
+        return Err(rbdc::Error::from("[rbatis] update has no column to set!"));
+    }
+    let quoted_keys: Vec<String> = keys.iter().map(|k| dialect.quote(k)).collect();
+    let when: Vec<String> = quoted_keys.iter().map(|k| format!("{} = ?", k)).collect();
+    let when = format!(" when {} then ?", when.join(" and "));
+    let mut sql = format!("update {} set ", table);
+    let mut args = vec![];
+    for (i, c) in columns.iter().enumerate() {
+        let c_quoted = dialect.quote(c);
+        if i > 0 {
+            sql.push(',');
+        }
+        sql.push_str(&format!("{} = case", c_quoted));
+        for row in &rows {
+            if let Some((_, v)) = row.sets.iter().find(|(name, _)| name == c) {
+                sql.push_str(&when);
+                args.extend(row.key.iter().cloned());
+                args.push(v.clone());
+            }
+        }
+        sql.push_str(&format!(" else {} end", c_quoted));
+    }
+    let (where_sql, where_args) = key_where(dialect, keys, rows.into_iter().map(|r| r.key).collect());
+    sql.push(' ');
+    sql.push_str(&where_sql);
+    args.extend(where_args);
+    Ok((sql, args))
+}
+
+/// what an upsert does with a row conflicting with an existing row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OnConflict {
+    /// update every inserted column except the conflict columns
+    Update,
+    /// update these columns
+    UpdateColumns(Vec<String>),
+    /// keep the existing row
+    DoNothing,
+}
+
+/// the upsert of `rows` values of `columns` into the quoted `table`, per `dialect.upsert()`.
+///
+/// `conflict` are the columns of the primary key or unique index,
+/// mysql ignores them and uses any unique key
+pub fn upsert_sql(
+    dialect: &dyn rbdc::dialect::Dialect,
+    table: &str,
+    columns: &[&str],
+    rows: Vec<Vec<rbs::Value>>,
+    conflict: &[&str],
+    on_conflict: &OnConflict,
+) -> Result<(String, Vec<rbs::Value>), rbdc::Error> {
+    if rows.is_empty() {
+        return Err(rbdc::Error::from("insert can not insert empty array tables!"));
+    }
+    if conflict.is_empty() {
+        return Err(rbdc::Error::from("[rbatis] upsert needs the conflict columns!"));
+    }
+    let updates: Vec<String> = match on_conflict {
+        OnConflict::Update => columns
+            .iter()
+            .filter(|c| !conflict.contains(c))
+            .map(|c| dialect.quote(c))
+            .collect(),
+        OnConflict::UpdateColumns(updates) => updates.iter().map(|c| dialect.quote(c)).collect(),
+        OnConflict::DoNothing => vec![],
+    };
+    let conflict: Vec<String> = conflict.iter().map(|c| dialect.quote(c)).collect();
+    let quoted: Vec<String> = columns.iter().map(|c| dialect.quote(c)).collect();
+    let args: Vec<rbs::Value> = rows.iter().flatten().cloned().collect();
+    if dialect.upsert() == rbdc::dialect::UpsertSyntax::Merge {
+        let sql = dialect.merge_sql(table, &quoted, rows.len(), &conflict, &updates);
+        return Ok((sql, args));
+    }
+    let row = format!("({})", vec!["?"; columns.len()].join(","));
+    let mut sql = format!(
+        "insert into {} ({}) values {}",
+        table,
+        quoted.join(","),
+        vec![row; rows.len()].join(",")
+    );
+    match dialect.upsert() {
+        rbdc::dialect::UpsertSyntax::OnConflict => {
+            sql.push_str(&format!(" on conflict ({}) do ", conflict.join(",")));
+            if updates.is_empty() {
+                sql.push_str("nothing");
+            } else {
+                let set: Vec<String> = updates
+                    .iter()
+                    .map(|u| format!("{} = excluded.{}", u, u))
+                    .collect();
+                sql.push_str("update set ");
+                sql.push_str(&set.join(","));
+            }
+        }
+        _ => {
+            // a no-op update keeps the existing row, `insert ignore` would ignore other errors too
+            let set: Vec<String> = if updates.is_empty() {
+                vec![format!("{} = {}", conflict[0], conflict[0])]
+            } else {
+                updates
+                    .iter()
+                    .map(|u| format!("{} = values({})", u, u))
+                    .collect()
+            };
+            sql.push_str(" on duplicate key update ");
+            sql.push_str(&set.join(","));
+        }
+    }
+    Ok((sql, args))
+}
+
+/// is the `id` null in every table, the database generates it then.
+/// a batch with some null ids only has no single column list and is an error
+pub(crate) fn id_is_null(tables: &[rbs::Value], id: &str) -> Result<bool, rbdc::Error> {
+    let nulls = tables.iter().filter(|table| table[id].is_null()).count();
+    if nulls != 0 && nulls != tables.len() {
+        return Err(rbdc::Error::from(format!(
+            "[rbatis] the id `{}` is null in {} of {} tables, insert the tables with and without ids in separate batches",
+            id,
+            nulls,
+            tables.len()
+        )));
+    }
+    Ok(nulls != 0)
+}
+
+/// drop the `ids` columns which are null in the tables, they are generated by the database
+pub fn skip_null_ids(ids: &[&str], tables: rbs::Value) -> Result<rbs::Value, rbdc::Error> {
+    let rows = match &tables {
+        rbs::Value::Array(arr) => arr.as_slice(),
+        table => std::slice::from_ref(table),
+    };
+    let mut skip = vec![];
+    for id in ids {
+        if id_is_null(rows, id)? {
+            skip.push(rbs::Value::String(id.to_string()));
+        }
+    }
+    Ok(match tables {
+        rbs::Value::Array(arr) => rbs::Value::Array(
+            arr.into_iter()
+                .map(|table| match table {
+                    rbs::Value::Map(mut m) => {
+                        for id in &skip {
+                            m.remove(id);
+                        }
+                        rbs::Value::Map(m)
+                    }
+                    table => table,
+                })
+                .collect(),
+        ),
+        tables => tables,
+    })
+}
+
+/// `table` with the generated `id` in its `field`, an integer or the string of a `String` field
+pub(crate) fn with_id<T: serde::Serialize + serde::de::DeserializeOwned>(
+    table: &T,
+    field: &str,
+    id: u64,
+) -> Result<T, rbdc::Error> {
+    let mut v = rbs::to_value(table)?;
+    // the `Patch` markers do not deserialize, they are null as in the rows of a `RETURNING`
+    if let rbs::Value::Map(m) = &mut v {
+        for (_, v) in m.0.iter_mut() {
+            plain_args(std::slice::from_mut(v));
+        }
+    }
+    v[field] = rbs::Value::U64(id);
+    if let Ok(table) = rbs::from_value(v.clone()) {
+        return Ok(table);
+    }
+    v[field] = rbs::Value::String(id.to_string());
+    rbs::from_value(v).map_err(|_| {
+        rbdc::Error::from(format!(
+            "[rbatis] the generated id field `{}` is neither an integer nor a string",
+            field
+        ))
+    })
+}
+
+/// insert the `tables` of `impl_insert!` and write the generated keys and defaults back,
+/// the columns are the fields and the null `ids` are generated.
+///
+/// the rows are returned by `RETURNING *` (postgres, sqlite) or `OUTPUT INSERTED.*` (mssql),
+/// mysql writes the auto increment id of one `ids` column back as `rbatis::table::insert_returning` does
+pub async fn insert_returning<T: serde::Serialize + serde::de::DeserializeOwned>(
+    rb: &mut dyn crate::executor::Executor,
+    table_name: &str,
+    ids: &[&str],
+    tables: &mut [T],
+) -> Result<rbdc::db::ExecResult, rbdc::Error> {
+    if tables.is_empty() {
+        return Err(rbdc::Error::from("insert can not insert empty array tables!"));
+    }
+    let values = rbs::to_value(&*tables)?;
+    // the auto increment id of mysql
+    let generated = match ids {
+        [id] if values[0][*id].is_null() => Some(*id),
+        _ => None,
+    };
+    let values = skip_null_ids(ids, values)?;
+    let dialect = rb.dialect()?;
+    let mut columns = vec![];
+    if let rbs::Value::Map(m) = &values[0] {
+        for (k, _) in m {
+            columns.push(k.as_str().unwrap_or_default().to_string());
+        }
+    }
+    let mut args = vec![];
+    if let rbs::Value::Array(arr) = &values {
+        for table in arr {
+            args.extend(columns.iter().map(|c| table[c.as_str()].clone()));
+        }
+    }
+    let quoted: Vec<String> = columns.iter().map(|c| dialect.quote(c)).collect();
+    let into = format!("insert into {} ({})", dialect.quote(table_name), quoted.join(","));
+    let rows = vec![format!("({})", vec!["?"; columns.len()].join(",")); tables.len()].join(",");
+    let sql = match dialect.returning() {
+        rbdc::dialect::ReturningSyntax::Returning => format!("{} values {} returning *", into, rows),
+        rbdc::dialect::ReturningSyntax::Output => format!("{} output inserted.* values {}", into, rows),
+        rbdc::dialect::ReturningSyntax::None => {
+            if let Some(id) = generated {
+                with_id(&tables[0], id, 1)?;
+            }
+            let r = rb.exec(&format!("{} values {}", into, rows), args).await?;
+            if let (Some(id), Some(first)) = (generated, r.last_insert_id.as_u64().filter(|id| *id > 0)) {
+                let n = (r.rows_affected as usize).min(tables.len());
+                for (i, table) in tables.iter_mut().take(n).enumerate() {
+                    *table = with_id(&*table, id, first + i as u64)?;
+                }
+            }
+            return Ok(r);
+        }
+    };
+    let rows: Vec<T> = crate::decode::decode(rb.fetch(&sql, args).await?)?;
+    if rows.len() != tables.len() {
+        return Err(rbdc::Error::from(format!(
+            "[rbatis] insert returned {} rows of {} tables",
+            rows.len(),
+            tables.len()
+        )));
+    }
+    for (table, row) in tables.iter_mut().zip(rows) {
+        *table = row;
+    }
+    let last_insert_id = match ids {
+        [id] => rbs::to_value(&tables[0])?[*id].clone(),
+        _ => rbs::Value::Null,
+    };
+    Ok(rbdc::db::ExecResult {
+        rows_affected: tables.len() as u64,
+        last_insert_id,
+    })
 }
 
 ///gen sql => INSERT INTO table_name (column1,column2,column3,...) VALUES (value1,value2,value3,...);
@@ -21,6 +499,12 @@ macro_rules! crud {
 /// #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 /// pub struct BizActivity{ pub id: Option<String> }
 /// rbatis::impl_insert!(BizActivity{});
+/// //the key columns, null keys are skipped
+/// //rbatis::impl_insert!(BizActivity{}, "biz_activity", ["tenant_id", "sku"]);
+/// //upsert
+/// //BizActivity::insert_or_update(&mut rb, &activity, &["id"], rbatis::OnConflict::Update).await;
+/// //write the generated id back
+/// //BizActivity::insert_returning(&mut rb, &mut activity).await;
 /// ```
 ///
 #[macro_export]
@@ -32,17 +516,28 @@ macro_rules! impl_insert {
         );
     };
     ($table:ty{},$table_name:expr) => {
+        $crate::impl_insert!($table{}, $table_name, ["id"]);
+    };
+    ($table:ty{},$table_name:expr,[$($id:expr),+ $(,)?]) => {
         impl $table {
-             pub async fn insert_batch(
+            /// split into statements under `dialect.max_params()` parameters
+            pub async fn insert_batch(
                 rb: &mut dyn $crate::executor::Executor,
                 tables: &[$table],
+            ) -> Result<rbdc::db::ExecResult, rbdc::Error> {
+                <$table>::insert_batch_chunked(rb, tables, 0).await
+            }
+
+            /// insert `chunk_size` tables a statement, 0 is the most under `dialect.max_params()`
+            pub async fn insert_batch_chunked(
+                rb: &mut dyn $crate::executor::Executor,
+                tables: &[$table],
+                chunk_size: usize,
             ) -> Result<rbdc::db::ExecResult, rbdc::Error> {
                 #[$crate::py_sql(
            "`insert into ${table_name} (`
              trim ',':
                for k,v in tables[0]:
-                  if k == 'id' && v== null:
-                    continue:
                  ${k},
              `) VALUES `
              trim ',':
@@ -50,15 +545,13 @@ macro_rules! impl_insert {
                (
                trim ',':
                 for k,v in table:
-                  if k == 'id' && v== null:
-                     continue:
                   #{v},
                ),
              "
                 )]
                 async fn do_insert_batch(
                     rb: &mut dyn $crate::executor::Executor,
-                    tables: &[$table],
+                    tables: &rbs::Value,
                     table_name: String,
                 ) -> Result<rbdc::db::ExecResult, rbdc::Error> {
                     impled!()
@@ -66,8 +559,67 @@ macro_rules! impl_insert {
                 if tables.is_empty() {
                     return Err(rbdc::Error::from("insert can not insert empty array tables!"));
                 }
-                let table_name = $table_name.to_string();
-                do_insert_batch(rb.into(), tables, table_name).await
+                let dialect = rb.dialect()?;
+                let table_name = dialect.quote(&$table_name.to_string());
+                let tables = $crate::crud::skip_null_ids(&[$($id),+], rbs::to_value!(tables))?;
+                let tables = $crate::crud::quote_keys(dialect, tables);
+                let chunk_size = match (chunk_size, &tables[0]) {
+                    (0, rbs::Value::Map(m)) => $crate::crud::chunk_size_of(dialect, m.len()),
+                    (0, _) => $crate::crud::chunk_size_of(dialect, 1),
+                    (n, _) => n,
+                };
+                let mut result: Option<rbdc::db::ExecResult> = None;
+                if let rbs::Value::Array(tables) = tables {
+                    for chunk in tables.chunks(chunk_size) {
+                        let chunk = rbs::Value::Array(chunk.to_vec());
+                        let r = do_insert_batch(rb, &chunk, table_name.clone()).await?;
+                        result = Some($crate::crud::merge_result(result, r));
+                    }
+                }
+                Ok(result.unwrap_or_default())
+            }
+
+            /// insert, or update the row conflicting on the `conflict_columns`
+            pub async fn insert_or_update(
+                rb: &mut dyn $crate::executor::Executor,
+                table: &$table,
+                conflict_columns: &[&str],
+                on_conflict: $crate::crud::OnConflict,
+            ) -> Result<rbdc::db::ExecResult, rbdc::Error> {
+                <$table>::insert_or_update_batch(rb, std::slice::from_ref(table), conflict_columns, on_conflict).await
+            }
+
+            pub async fn insert_or_update_batch(
+                rb: &mut dyn $crate::executor::Executor,
+                tables: &[$table],
+                conflict_columns: &[&str],
+                on_conflict: $crate::crud::OnConflict,
+            ) -> Result<rbdc::db::ExecResult, rbdc::Error> {
+                if tables.is_empty() {
+                    return Err(rbdc::Error::from("insert can not insert empty array tables!"));
+                }
+                let tables = $crate::crud::skip_null_ids(&[$($id),+], rbs::to_value!(tables))?;
+                let mut columns = vec![];
+                if let rbs::Value::Map(m) = &tables[0] {
+                    for (k, _) in m {
+                        columns.push(k.as_str().unwrap_or_default().to_string());
+                    }
+                }
+                let columns: Vec<&str> = columns.iter().map(|c| c.as_str()).collect();
+                let mut rows: Vec<Vec<rbs::Value>> = vec![];
+                if let rbs::Value::Array(arr) = &tables {
+                    for table in arr {
+                        rows.push(columns.iter().map(|c| table[*c].clone()).collect());
+                    }
+                }
+                let mut result = None;
+                for chunk in rows.chunks($crate::crud::chunk_size_of(rb.dialect()?, columns.len())) {
+                    let dialect = rb.dialect()?;
+                    let table_name = dialect.quote(&$table_name.to_string());
+                    let (sql, args) = $crate::crud::upsert_sql(dialect, &table_name, &columns, chunk.to_vec(), conflict_columns, &on_conflict)?;
+                    result = Some($crate::crud::merge_result(result, rb.exec(&sql, args).await?));
+                }
+                Ok(result.unwrap_or_default())
             }
 
             pub async fn insert(
@@ -76,6 +628,21 @@ macro_rules! impl_insert {
             ) -> Result<rbdc::db::ExecResult, rbdc::Error> {
                 <$table>::insert_batch(rb,&[table.clone()]).await
             }
+
+            /// insert and write the generated keys and defaults back into `table`, see `rbatis::crud::insert_returning`
+            pub async fn insert_returning(
+                rb: &mut dyn $crate::executor::Executor,
+                table: &mut $table,
+            ) -> Result<rbdc::db::ExecResult, rbdc::Error> {
+                $crate::crud::insert_returning(rb, &$table_name.to_string(), &[$($id),+], std::slice::from_mut(table)).await
+            }
+
+            pub async fn insert_batch_returning(
+                rb: &mut dyn $crate::executor::Executor,
+                tables: &mut [$table],
+            ) -> Result<rbdc::db::ExecResult, rbdc::Error> {
+                $crate::crud::insert_returning(rb, &$table_name.to_string(), &[$($id),+], tables).await
+            }
         }
     };
 }
@@ -90,6 +657,10 @@ macro_rules! impl_insert {
 ///rbatis::impl_select!(BizActivity{select_all_by_id(id:&str,name:&str) => "select * from biz_activity where id = #{id} and name = #{name}"});
 ///rbatis::impl_select!(BizActivity{select_by_id(id:String) -> Option => "select * from biz_activity where id = #{id} limit 1"});
 ///
+/// //with key columns, gen select_by_id(key) and select_by_id_batch(&[key]), a key of more columns is a tuple
+/// //rbatis::impl_select!(BizActivity{}, "biz_activity", ["tenant_id", "sku"]);
+/// //BizActivity::select_by_id(&mut rb, (1, "a")).await;
+///
 /// //use
 /// //BizActivity::select**()
 /// ```
@@ -104,16 +675,91 @@ macro_rules! impl_select {
             pub async fn select_all(rb: &mut dyn  $crate::executor::Executor)->Result<Vec<$table>,rbdc::Error>{
                 #[$crate::py_sql("select * from ${table_name}")]
                 async fn do_select_all(rb: &mut dyn $crate::executor::Executor,table_name:String) -> Result<Vec<$table>,rbdc::Error> {impled!()}
-                let table_name = $table_name.to_string();
+                let table_name = rb.dialect()?.quote(&$table_name.to_string());
                 do_select_all(rb,table_name).await
             }
 
             pub async fn select_by_column<V:serde::Serialize>(rb: &mut dyn  $crate::executor::Executor, column: &str,column_value:V)->Result<Vec<$table>,rbdc::Error>{
                 #[$crate::py_sql("select * from ${table_name} where ${column} = #{column_value}")]
                 async fn do_select_by_column(rb: &mut dyn $crate::executor::Executor,table_name:String, column:&str, column_value: &rbs::Value) -> Result<Vec<$table>,rbdc::Error> {impled!()}
-                let table_name = $table_name.to_string();
+                let dialect = rb.dialect()?;
+                let table_name = dialect.quote(&$table_name.to_string());
+                let column = dialect.quote(column);
                 let column_value = rbs::to_value!(column_value);
-                do_select_by_column(rb,table_name,column,&column_value).await
+                do_select_by_column(rb,table_name,&column,&column_value).await
+            }
+
+            /// select by the wrapper, with its `group by`, `order by` and `limit`
+            pub async fn select_by_wrapper(rb: &mut dyn $crate::executor::Executor, w: &$crate::sql::QueryWrapper)->Result<Vec<$table>,rbdc::Error>{
+                let dialect = rb.dialect()?;
+                let (where_sql, args) = w.sql(dialect)?;
+                let sql = format!("select * from {} {}", dialect.quote(&$table_name.to_string()), where_sql);
+                $crate::decode(rb.fetch(sql.trim_end(), args).await?)
+            }
+
+            pub async fn count_by_wrapper(rb: &mut dyn $crate::executor::Executor, w: &$crate::sql::QueryWrapper)->Result<u64,rbdc::Error>{
+                let dialect = rb.dialect()?;
+                let (where_sql, args) = w.where_sql(dialect)?;
+                let sql = format!("select count(1) as count from {} {}", dialect.quote(&$table_name.to_string()), where_sql);
+                $crate::decode(rb.fetch(sql.trim_end(), args).await?)
+            }
+
+            /// `select 1 .. limit 1`, no row is transferred
+            pub async fn exists_by_wrapper(rb: &mut dyn $crate::executor::Executor, w: &$crate::sql::QueryWrapper)->Result<bool,rbdc::Error>{
+                let dialect = rb.dialect()?;
+                let (where_sql, args) = w.where_sql(dialect)?;
+                let sql = format!("select 1 from {} {}", dialect.quote(&$table_name.to_string()), where_sql);
+                let sql = dialect.page_sql(sql.trim_end(), 0, 1);
+                let rows = rb.fetch(&sql, args).await?;
+                Ok(rows.as_array().map(|rows| !rows.is_empty()).unwrap_or_default())
+            }
+
+            /// select by the `and` of the entries of a map, an array value is `in (..)`, a null value `is null`
+            pub async fn select_by_map<M:serde::Serialize>(rb: &mut dyn $crate::executor::Executor, condition: M)->Result<Vec<$table>,rbdc::Error>{
+                let w = $crate::sql::QueryWrapper::from_map(rbs::to_value!(condition))?;
+                <$table>::select_by_wrapper(rb, &w).await
+            }
+
+            pub async fn count_by_map<M:serde::Serialize>(rb: &mut dyn $crate::executor::Executor, condition: M)->Result<u64,rbdc::Error>{
+                let w = $crate::sql::QueryWrapper::from_map(rbs::to_value!(condition))?;
+                <$table>::count_by_wrapper(rb, &w).await
+            }
+
+            pub async fn exists_by_map<M:serde::Serialize>(rb: &mut dyn $crate::executor::Executor, condition: M)->Result<bool,rbdc::Error>{
+                let w = $crate::sql::QueryWrapper::from_map(rbs::to_value!(condition))?;
+                <$table>::exists_by_wrapper(rb, &w).await
+            }
+
+            pub async fn count_by_column<V:serde::Serialize>(rb: &mut dyn $crate::executor::Executor, column: &str, column_value: V)->Result<u64,rbdc::Error>{
+                <$table>::count_by_wrapper(rb, &$crate::sql::QueryWrapper::new().eq(column, column_value)).await
+            }
+
+            pub async fn exists_by_column<V:serde::Serialize>(rb: &mut dyn $crate::executor::Executor, column: &str, column_value: V)->Result<bool,rbdc::Error>{
+                <$table>::exists_by_wrapper(rb, &$crate::sql::QueryWrapper::new().eq(column, column_value)).await
+            }
+        }
+    };
+    ($table:ty{},$table_name:expr,[$($id:expr),+ $(,)?]) => {
+        $crate::impl_select!($table{},$table_name);
+        impl $table{
+            pub async fn select_by_id<K:serde::Serialize>(rb: &mut dyn $crate::executor::Executor, id: K)->Result<Option<$table>,rbdc::Error>{
+                let r = <$table>::select_by_id_batch(rb, &[id]).await?;
+                Ok(r.into_iter().next())
+            }
+
+            pub async fn select_by_id_batch<K:serde::Serialize>(rb: &mut dyn $crate::executor::Executor, ids: &[K])->Result<Vec<$table>,rbdc::Error>{
+                if ids.is_empty() {
+                    return Ok(vec![]);
+                }
+                let id_columns = [$($id),+];
+                let mut keys = Vec::with_capacity(ids.len());
+                for id in ids {
+                    keys.push($crate::crud::key_values(&id_columns, rbs::to_value!(id))?);
+                }
+                let dialect = rb.dialect()?;
+                let (where_sql, args) = $crate::crud::key_where(dialect, &id_columns, keys);
+                let sql = format!("select * from {} {}", dialect.quote(&$table_name.to_string()), where_sql);
+                $crate::decode(rb.fetch(&sql, args).await?)
             }
         }
     };
@@ -127,7 +773,7 @@ macro_rules! impl_select {
                  }else{
                      #[$crate::py_sql("`select * from ${table_name} `",$sql)]
                      async fn do_select_all(rb: &mut dyn $crate::executor::Executor,table_name:&str,$($param_key:$param_type,)*) -> Result<Vec<$table>,rbdc::Error> {impled!()}
-                     let table_name = $crate::utils::string_util::to_snake_name(stringify!($table));
+                     let table_name = rb.dialect()?.quote(&$crate::utils::string_util::to_snake_name(stringify!($table)));
                      do_select_all(rb,&table_name,$($param_key ,)*).await
                  }
             }
@@ -150,7 +796,7 @@ macro_rules! impl_select {
                 }else{
                      #[$crate::py_sql("`select * from ${table_name} `",$sql)]
                      async fn do_select_all(rb: &mut dyn $crate::executor::Executor,table_name:&str,$($param_key:$param_type,)*) -> Result<$container<$table>,rbdc::Error> {impled!()}
-                     let table_name = $crate::utils::string_util::to_snake_name(stringify!($table));
+                     let table_name = rb.dialect()?.quote(&$crate::utils::string_util::to_snake_name(stringify!($table)));
                      do_select_all(rb,&table_name,$($param_key ,)*).await
                 }
             }
@@ -163,6 +809,8 @@ macro_rules! impl_select {
 /// #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 /// pub struct BizActivity{ pub id: Option<String> }
 /// rbatis::impl_update!(BizActivity{});
+/// //with key columns, gen update_by_id(&table) and update_by_id_batch(&[table])
+/// //rbatis::impl_update!(BizActivity{}, "biz_activity", ["tenant_id", "sku"]);
 /// ```
 #[macro_export]
 macro_rules! impl_update {
@@ -174,16 +822,36 @@ macro_rules! impl_update {
     };
     ($table:ty{},$table_name:expr) => {
         impl $table {
+            /// update the not null columns, `Patch::Null` columns are set to null
             pub async fn update_by_column(
                 rb: &mut dyn $crate::executor::Executor,
                 table: &$table,
                 column: &str,
+            ) -> Result<rbdc::db::ExecResult, rbdc::Error> {
+                <$table>::update_by_column_skip(rb, table, column, true).await
+            }
+
+            /// update every column, null columns are set to null
+            pub async fn update_by_column_with_nulls(
+                rb: &mut dyn $crate::executor::Executor,
+                table: &$table,
+                column: &str,
+            ) -> Result<rbdc::db::ExecResult, rbdc::Error> {
+                <$table>::update_by_column_skip(rb, table, column, false).await
+            }
+
+            /// `skip_null` skips the null columns, `Patch::Unset` columns are skipped always
+            pub async fn update_by_column_skip(
+                rb: &mut dyn $crate::executor::Executor,
+                table: &$table,
+                column: &str,
+                skip_null: bool,
             ) -> Result<rbdc::db::ExecResult, rbdc::Error> {
                 #[$crate::py_sql(
                     "`update ${table_name} set `
              trim ',':
                for k,v in table:
-                  if k == column || v== null:
+                  if k == column:
                     continue:
                  `${k}=#{v},`
              ` where  ${column} = #{column_value}`"
@@ -197,19 +865,160 @@ macro_rules! impl_update {
                 ) -> Result<rbdc::db::ExecResult, rbdc::Error> {
                     impled!()
                 }
-                let table_name = $table_name.to_string();
                 let table = rbs::to_value!(table);
-                let column_value = &table[column];
-                do_update_by_column(rb, table_name, &table, column_value, column).await
+                let column_value = table[column].clone();
+                let dialect = rb.dialect()?;
+                let table_name = dialect.quote(&$table_name.to_string());
+                let column = dialect.quote(column);
+                let table = $crate::crud::update_columns($crate::crud::quote_keys(dialect, table), !skip_null);
+                do_update_by_column(rb, table_name, &table, &column_value, &column).await
+            }
+
+            /// update the not null columns of the rows matching the wrapper, which must have a condition
+            pub async fn update_by_wrapper(
+                rb: &mut dyn $crate::executor::Executor,
+                table: &$table,
+                w: &$crate::sql::QueryWrapper,
+            ) -> Result<rbdc::db::ExecResult, rbdc::Error> {
+                if w.is_empty() {
+                    return Err(rbdc::Error::from("[rbatis] update_by_wrapper needs a condition!"));
+                }
+                let dialect = rb.dialect()?;
+                let mut sql = format!("update {} set ", dialect.quote(&$table_name.to_string()));
+                let mut args = vec![];
+                if let rbs::Value::Map(m) = $crate::crud::update_columns(rbs::to_value!(table), false) {
+                    for (k, v) in m {
+                        if !args.is_empty() {
+                            sql.push(',');
+                        }
+                        sql.push_str(&dialect.quote(k.as_str().unwrap_or_default()));
+                        sql.push_str(" = ?");
+                        args.push(v);
+                    }
+                }
+                if args.is_empty() {
+                    return Err(rbdc::Error::from("[rbatis] update has no column to set!"));
+                }
+                let (where_sql, where_args) = w.where_sql(dialect)?;
+                sql.push(' ');
+                sql.push_str(&where_sql);
+                args.extend(where_args);
+                rb.exec(&sql, args).await
             }
+
+            /// one `update .. set a = case .. end` statement, or `update_by_column` of every table
+            /// in a transaction if the statement has more than `dialect.max_params()` parameters
             pub async fn update_by_column_batch(
                 rb: &mut dyn $crate::executor::Executor,
                 tables: &[$table],
                 column: &str,
+            ) -> Result<rbdc::db::ExecResult, rbdc::Error> {
+                async fn update_each(
+                    rb: &mut dyn $crate::executor::Executor,
+                    tables: &[$table],
+                    column: &str,
+                ) -> Result<rbdc::db::ExecResult, rbdc::Error> {
+                    let mut rows_affected = 0;
+                    for item in tables{
+                        rows_affected += <$table>::update_by_column(rb,item,column).await?.rows_affected;
+                    }
+                    Ok(rbdc::db::ExecResult{
+                        rows_affected:rows_affected,
+                        last_insert_id:rbs::Value::Null
+                    })
+                }
+                if tables.is_empty() {
+                    return Ok(rbdc::db::ExecResult::default());
+                }
+                let mut rows = Vec::with_capacity(tables.len());
+                let mut params = 0;
+                for table in tables {
+                    let table = rbs::to_value!(table);
+                    let mut row = $crate::crud::UpdateRow {
+                        key: vec![table[column].clone()],
+                        sets: vec![],
+                    };
+                    if let rbs::Value::Map(m) = $crate::crud::update_columns(table, false) {
+                        for (k, v) in m {
+                            let k = k.as_str().unwrap_or_default();
+                            if k == column {
+                                continue;
+                            }
+                            row.sets.push((k.to_string(), v));
+                        }
+                    }
+                    params += row.sets.len() * 2 + 1;
+                    rows.push(row);
+                }
+                let dialect = rb.dialect()?;
+                if params <= dialect.max_params() {
+                    let table_name = dialect.quote(&$table_name.to_string());
+                    let (sql, args) = $crate::crud::update_batch_sql(dialect, &table_name, &[column], rows)?;
+                    return rb.exec(&sql, args).await;
+                }
+                if rb.in_transaction() {
+                    return update_each(rb, tables, column).await;
+                }
+                let mut tx = rb.get_rbatis().acquire_begin().await?;
+                match update_each(&mut tx, tables, column).await {
+                    Ok(r) => {
+                        if !tx.commit().await? {
+                            return Err(rbdc::Error::from("[rbatis] update_by_column_batch commit fail!"));
+                        }
+                        Ok(r)
+                    }
+                    Err(e) => {
+                        tx.rollback().await?;
+                        Err(e)
+                    }
+                }
+            }
+        }
+    };
+    ($table:ty{},$table_name:expr,[$($id:expr),+ $(,)?]) => {
+        $crate::impl_update!($table{},$table_name);
+        impl $table {
+            pub async fn update_by_id(
+                rb: &mut dyn $crate::executor::Executor,
+                table: &$table,
+            ) -> Result<rbdc::db::ExecResult, rbdc::Error> {
+                let id_columns = [$($id),+];
+                let table = rbs::to_value!(table);
+                let dialect = rb.dialect()?;
+                let mut sql = format!("update {} set ", dialect.quote(&$table_name.to_string()));
+                let mut args = vec![];
+                if let rbs::Value::Map(m) = $crate::crud::update_columns(table.clone(), false) {
+                    for (k, v) in m {
+                        let k = k.as_str().unwrap_or_default();
+                        if id_columns.contains(&k) {
+                            continue;
+                        }
+                        if !args.is_empty() {
+                            sql.push(',');
+                        }
+                        sql.push_str(&dialect.quote(k));
+                        sql.push_str(" = ?");
+                        args.push(v.clone());
+                    }
+                }
+                if args.is_empty() {
+                    return Err(rbdc::Error::from("[rbatis] update has no column to set!"));
+                }
+                let key = id_columns.iter().map(|id| table[*id].clone()).collect();
+                let (where_sql, key_args) = $crate::crud::key_where(dialect, &id_columns, vec![key]);
+                sql.push(' ');
+                sql.push_str(&where_sql);
+                args.extend(key_args);
+                rb.exec(&sql, args).await
+            }
+
+            pub async fn update_by_id_batch(
+                rb: &mut dyn $crate::executor::Executor,
+                tables: &[$table],
             ) -> Result<rbdc::db::ExecResult, rbdc::Error> {
                 let mut rows_affected = 0;
                 for item in tables{
-                    rows_affected += <$table>::update_by_column(rb,item,column).await?.rows_affected;
+                    rows_affected += <$table>::update_by_id(rb,item).await?.rows_affected;
                 }
                 Ok(rbdc::db::ExecResult{
                     rows_affected:rows_affected,
@@ -244,22 +1053,23 @@ macro_rules! impl_update {
                 } else {
                   #[$crate::py_sql("`update ${table_name} set  `
                                  trim ',':
-                                   for k,v in table:
-                                     if k == column || v== null:
-                                        continue:
+                                   for k,v in quoted_table:
                                      `${k}=#{v},`
                                  ` `",$sql_where)]
                   async fn do_update_by_where(
                       rb: &mut dyn $crate::executor::Executor,
                       table_name: String,
                       table: &rbs::Value,
+                      quoted_table: &rbs::Value,
                       $($param_key:$param_type,)*
                   ) -> Result<rbdc::db::ExecResult, rbdc::Error> {
                       impled!()
                   }
-                  let table_name = $crate::utils::string_util::to_snake_name(stringify!($table));
+                  let dialect = rb.dialect()?;
+                  let table_name = dialect.quote(&$crate::utils::string_util::to_snake_name(stringify!($table)));
                   let table = rbs::to_value!(table);
-                  do_update_by_where(rb, table_name, &table, $($param_key,)*).await
+                  let quoted_table = $crate::crud::update_columns($crate::crud::quote_keys(dialect, table.clone()), false);
+                  do_update_by_where(rb, table_name, &table, &quoted_table, $($param_key,)*).await
                 }
             }
         }
@@ -272,6 +1082,8 @@ macro_rules! impl_update {
 /// #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 /// pub struct BizActivity{}
 /// rbatis::impl_delete!(BizActivity{});
+/// //with key columns, gen delete_by_id(key) and delete_by_id_batch(&[key])
+/// //rbatis::impl_delete!(BizActivity{}, "biz_activity", ["tenant_id", "sku"]);
 /// ```
 #[macro_export]
 macro_rules! impl_delete {
@@ -298,8 +1110,10 @@ macro_rules! impl_delete {
                     impled!()
                 }
                 let column_value = rbs::to_value!(column_value);
-                let table_name = $table_name.to_string();
-                do_delete_by_column(rb, table_name, &column_value, column).await
+                let dialect = rb.dialect()?;
+                let table_name = dialect.quote(&$table_name.to_string());
+                let column = dialect.quote(column);
+                do_delete_by_column(rb, table_name, &column_value, &column).await
             }
             pub async fn delete_by_column_batch<V:serde::Serialize>(
                 rb: &mut dyn $crate::executor::Executor,
@@ -319,9 +1133,62 @@ macro_rules! impl_delete {
                 ) -> Result<rbdc::db::ExecResult, rbdc::Error> {
                     impled!()
                 }
-                let column_values = rbs::to_value!(column_values);
-                let table_name = $table_name.to_string();
-                do_delete_by_column_batch(rb, table_name, column_values, column).await
+                let dialect = rb.dialect()?;
+                let table_name = dialect.quote(&$table_name.to_string());
+                let column = dialect.quote(column);
+                let mut result = None;
+                for chunk in column_values.chunks($crate::crud::chunk_size_of(dialect, 1)) {
+                    let chunk = rbs::to_value!(chunk);
+                    result = Some($crate::crud::merge_result(result, do_delete_by_column_batch(rb, table_name.clone(), chunk, &column).await?));
+                }
+                Ok(result.unwrap_or_default())
+            }
+
+            /// delete the rows matching the wrapper, which must have a condition
+            pub async fn delete_by_wrapper(
+                rb: &mut dyn $crate::executor::Executor,
+                w: &$crate::sql::QueryWrapper,
+            ) -> Result<rbdc::db::ExecResult, rbdc::Error> {
+                if w.is_empty() {
+                    return Err(rbdc::Error::from("[rbatis] delete_by_wrapper needs a condition!"));
+                }
+                let dialect = rb.dialect()?;
+                let (where_sql, args) = w.where_sql(dialect)?;
+                let sql = format!("delete from {} {}", dialect.quote(&$table_name.to_string()), where_sql);
+                rb.exec(&sql, args).await
+            }
+        }
+    };
+    ($table:ty{},$table_name:expr,[$($id:expr),+ $(,)?]) => {
+        $crate::impl_delete!($table{},$table_name);
+        impl $table {
+            pub async fn delete_by_id<K:serde::Serialize>(
+                rb: &mut dyn $crate::executor::Executor,
+                id: K,
+            ) -> Result<rbdc::db::ExecResult, rbdc::Error> {
+                <$table>::delete_by_id_batch(rb, &[id]).await
+            }
+
+            pub async fn delete_by_id_batch<K:serde::Serialize>(
+                rb: &mut dyn $crate::executor::Executor,
+                ids: &[K],
+            ) -> Result<rbdc::db::ExecResult, rbdc::Error> {
+                if ids.is_empty() {
+                    return Ok(rbdc::db::ExecResult::default());
+                }
+                let id_columns = [$($id),+];
+                let mut result = None;
+                for chunk in ids.chunks($crate::crud::chunk_size_of(rb.dialect()?, id_columns.len())) {
+                    let mut keys = Vec::with_capacity(chunk.len());
+                    for id in chunk {
+                        keys.push($crate::crud::key_values(&id_columns, rbs::to_value!(id))?);
+                    }
+                    let dialect = rb.dialect()?;
+                    let (where_sql, args) = $crate::crud::key_where(dialect, &id_columns, keys);
+                    let sql = format!("delete from {} {}", dialect.quote(&$table_name.to_string()), where_sql);
+                    result = Some($crate::crud::merge_result(result, rb.exec(&sql, args).await?));
+                }
+                Ok(result.unwrap_or_default())
             }
         }
     };
@@ -342,22 +1209,51 @@ macro_rules! impl_delete {
                 ) -> Result<rbdc::db::ExecResult, rbdc::Error> {
                     impled!()
                 }
-                let table_name = $crate::utils::string_util::to_snake_name(stringify!($table));
+                let table_name = rb.dialect()?.quote(&$crate::utils::string_util::to_snake_name(stringify!($table)));
                 do_delete_by_where(rb, table_name, $($param_key,)*).await
             }
         }
     };
 }
 
+/// gen a page select of the rows of `where_sql`, `#{page_no}` and `#{page_size}` are the offset and size
+/// of a `where_sql` with its own limit, else the dialect limit is appended.
+///
+/// the total is counted without the `order by` and limit, skipped if not `page_req.search_count`,
+/// or counted in the same select by `page_req.count_over` on dialects of window functions.
+///
+/// `page_req.order_by` replaces the `order by` of `where_sql`, only of the sortable columns in `[..]`
+/// ```rust
+/// #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+/// pub struct BizActivity{ pub id: Option<String>, pub name: Option<String> }
+/// rbatis::impl_select_page!(BizActivity{select_page(name:&str) => "`where name != #{name} order by id`"}, ["id", "create_time"]);
+/// //let req = rbatis::sql::PageRequest::new(1, 10).order_by(vec![("create_time", rbatis::sql::Order::Desc)]);
+/// //let page = BizActivity::select_page(&mut rb, &req.set_count_over(true), "").await?;
+/// ```
 #[macro_export]
 macro_rules! impl_select_page {
+    ($table:ty{$fn_name:ident($($param_key:ident:$param_type:ty$(,)?)*) => $where_sql:expr},[$($sortable:expr),* $(,)?]) => {
+        $crate::impl_select_page!(
+            $table{$fn_name($($param_key:$param_type,)*)=> $where_sql},
+            $crate::utils::string_util::to_snake_name(stringify!($table)),
+            [$($sortable),*]
+        );
+    };
     ($table:ty{$fn_name:ident($($param_key:ident:$param_type:ty$(,)?)*) => $where_sql:expr}) => {
         $crate::impl_select_page!(
             $table{$fn_name($($param_key:$param_type)*)=> $where_sql},
-            $crate::utils::string_util::to_snake_name(stringify!($table))
+            $crate::utils::string_util::to_snake_name(stringify!($table)),
+            []
         );
     };
     ($table:ty{$fn_name:ident($($param_key:ident:$param_type:ty$(,)?)*) => $where_sql:expr},$table_name:expr) => {
+        $crate::impl_select_page!(
+            $table{$fn_name($($param_key:$param_type,)*)=> $where_sql},
+            $table_name,
+            []
+        );
+    };
+    ($table:ty{$fn_name:ident($($param_key:ident:$param_type:ty$(,)?)*) => $where_sql:expr},$table_name:expr,[$($sortable:expr),* $(,)?]) => {
         impl $table {
             pub async fn $fn_name(
                 rb: &mut dyn $crate::executor::Executor,
@@ -365,24 +1261,61 @@ macro_rules! impl_select_page {
                 $($param_key:$param_type,)*
             ) -> Result<$crate::sql::Page::<$table>, rbdc::Error> {
                 use $crate::sql::IPageRequest;
-                #[$crate::py_sql("`select count(1) as count from ${table_name} `",$where_sql)]
-                async fn do_select_page_count(rb: &mut dyn $crate::executor::Executor,table_name: &str,$($param_key:$param_type,)*) -> Result<u64, rbdc::Error> {impled!()}
-                let table_name = $table_name.to_string();
-                let total:u64=do_select_page_count(rb, &table_name, $($param_key,)*).await?;
-                let records:Vec<$table>;
-                if $where_sql.contains("page_no") && $where_sql.contains("page_size"){
-                    #[$crate::py_sql("`select * from ${table_name} `",$where_sql)]
-                    async fn do_select_page(rb: &mut dyn $crate::executor::Executor,table_name: &str,page_no:u64,page_size:u64,$($param_key:$param_type,)*) -> Result<Vec<$table>, rbdc::Error> {impled!()}
-                    records = do_select_page(rb,&table_name,page_req.offset(), page_req.page_size,$($param_key,)*).await?;
-                }else{
-                    #[$crate::py_sql("`select * from ${table_name} `",$where_sql,"
-                              ` limit ${page_no},${page_size}`")]
-                    async fn do_select_page(rb: &mut dyn $crate::executor::Executor,table_name: &str,page_no:u64,page_size:u64,$($param_key:$param_type,)*) -> Result<Vec<$table>, rbdc::Error> {impled!()}
-                    records = do_select_page(rb,&table_name,page_req.offset(), page_req.page_size,$($param_key,)*).await?;
-                }
-                let mut page = $crate::sql::Page::<$table>::new_total(page_req.page_no, page_req.page_size, total);
-                page.records = records;
-                Ok(page)
+                // the where_sql is rendered on its own, the tail of `select * from table`
+                #[$crate::py_sql("` `",$where_sql)]
+                async fn do_select_page(rb: &mut dyn $crate::executor::Executor,page_no:u64,page_size:u64,$($param_key:$param_type,)*) -> Result<Vec<$table>, rbdc::Error> {impled!()}
+                let table_name = rb.dialect()?.quote(&$table_name.to_string());
+                let mut capture = $crate::executor::SqlCapture::new(rb.get_rbatis());
+                do_select_page(&mut capture, page_req.offset(), page_req.page_size, $($param_key,)*).await?;
+                let (sql, args) = (capture.sql, capture.args);
+                let tail = match sql.trim() {
+                    "" => String::new(),
+                    sql => format!(" {}", sql),
+                };
+                let tail = tail.as_str();
+                let paged = $where_sql.contains("page_no") && $where_sql.contains("page_size");
+                let sortable: &[&str] = &[$($sortable),*];
+                $crate::sql::select_page(rb, page_req, "*", &table_name, sortable, tail, args, paged).await
+            }
+        }
+    };
+}
+
+/// gen a keyset (cursor) page select of the rows of `where_sql` ordered by the unique key columns,
+/// no `count` and no `offset`, deep pages are as fast as the first:
+///
+/// `select * from (select * from t where ..) t_cursor where (a, b) > (?, ?) order by a, b limit n`
+/// ```rust
+/// #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+/// pub struct BizActivity{ pub id: Option<String>, pub create_time: Option<String> }
+/// rbatis::impl_select_cursor!(BizActivity{select_cursor(name:&str) => "`where name != #{name}`"}, ["create_time", "id"]);
+/// //let page = BizActivity::select_cursor(&mut rb, &rbatis::sql::CursorRequest::new(10), "").await?;
+/// //the page after, page.prev_cursor is the page before
+/// //let req = rbatis::sql::CursorRequest::with_cursor(&page.next_cursor.unwrap(), 10);
+/// ```
+#[macro_export]
+macro_rules! impl_select_cursor {
+    ($table:ty{$fn_name:ident($($param_key:ident:$param_type:ty$(,)?)*) => $where_sql:expr},[$($key:expr),+ $(,)?]) => {
+        $crate::impl_select_cursor!(
+            $table{$fn_name($($param_key:$param_type,)*) => $where_sql},
+            $crate::utils::string_util::to_snake_name(stringify!($table)),
+            [$($key),+]
+        );
+    };
+    ($table:ty{$fn_name:ident($($param_key:ident:$param_type:ty$(,)?)*) => $where_sql:expr},$table_name:expr,[$($key:expr),+ $(,)?]) => {
+        impl $table {
+            pub async fn $fn_name(
+                rb: &mut dyn $crate::executor::Executor,
+                cursor_req: &$crate::sql::CursorRequest,
+                $($param_key:$param_type,)*
+            ) -> Result<$crate::sql::CursorPage::<$table>, rbdc::Error> {
+                #[$crate::py_sql("`select * from ${table_name} `",$where_sql)]
+                async fn do_select_cursor(rb: &mut dyn $crate::executor::Executor,table_name: &str,$($param_key:$param_type,)*) -> Result<Vec<$table>, rbdc::Error> {impled!()}
+                let table_name = rb.dialect()?.quote(&$table_name.to_string());
+                let mut capture = $crate::executor::SqlCapture::new(rb.get_rbatis());
+                do_select_cursor(&mut capture, &table_name, $($param_key,)*).await?;
+                let (sql, args) = (capture.sql, capture.args);
+                $crate::sql::select_cursor(rb, cursor_req, &[$($key),+], sql.trim_end(), args).await
             }
         }
     };
@@ -11,8 +11,11 @@ use flume::RecvError;
 use futures::executor::block_on;
 use futures::Future;
 use futures_core::future::BoxFuture;
+use crate::crud::plain_args;
 use crate::decode::decode;
-use rbdc::db::{Connection, ExecResult};
+use rbdc::common::named_placeholder;
+use rbdc::db::{Connection, ExecResult, Statement, StatementColumn};
+use rbdc::dialect::Dialect;
 use rbs::{from_value, Value};
 use serde::de::DeserializeOwned;
 use serde::{Serialize, Serializer};
@@ -23,6 +26,76 @@ use crate::Error;
 pub trait Executor: RbatisRef {
     async fn exec(&mut self, sql: &str, args: Vec<Value>) -> Result<ExecResult, Error>;
     async fn fetch(&mut self, sql: &str, args: Vec<Value>) -> Result<Value, Error>;
+
+    /// exec one sql with many args, one ExecResult per args.
+    /// connections send all args in one pipeline, the default runs exec once per args
+    async fn exec_batch(
+        &mut self,
+        sql: &str,
+        args: Vec<Vec<Value>>,
+    ) -> Result<Vec<ExecResult>, Error> {
+        let mut results = Vec::with_capacity(args.len());
+        for x in args {
+            results.push(self.exec(sql, x).await?);
+        }
+        Ok(results)
+    }
+
+    /// prepare a statement on the connection of this executor, see `Connection::prepare`
+    async fn prepare_statement(&mut self, sql: &str) -> Result<Statement, Error> {
+        let _ = sql;
+        Err(Error::from("prepare needs a connection, use acquire() or acquire_begin()"))
+    }
+
+    /// close a statement prepared by `prepare_statement`
+    async fn close_statement(&mut self, sql: &str) -> Result<(), Error> {
+        let _ = sql;
+        Ok(())
+    }
+
+    /// true if this executor runs inside a transaction
+    fn in_transaction(&self) -> bool {
+        false
+    }
+}
+
+/// a statement prepared on a connection or tx, `exec`/`fetch` skip the parse step of the database.
+/// the statement stays cached on the connection until `close()`
+pub struct Prepared<'a> {
+    pub statement: Statement,
+    executor: &'a mut dyn Executor,
+}
+
+impl<'a> Prepared<'a> {
+    /// the type name of every parameter
+    pub fn param_types(&self) -> &[String] {
+        &self.statement.param_types
+    }
+
+    pub fn columns(&self) -> &[StatementColumn] {
+        &self.statement.columns
+    }
+
+    pub async fn exec(&mut self, args: Vec<Value>) -> Result<ExecResult, Error> {
+        self.executor.exec(&self.statement.sql, args).await
+    }
+
+    pub async fn fetch(&mut self, args: Vec<Value>) -> Result<Value, Error> {
+        self.executor.fetch(&self.statement.sql, args).await
+    }
+
+    pub async fn fetch_decode<T>(&mut self, args: Vec<Value>) -> Result<T, Error>
+        where
+            T: DeserializeOwned,
+    {
+        let v = self.fetch(args).await?;
+        decode(v)
+    }
+
+    /// free the statement on the server
+    pub async fn close(self) -> Result<(), Error> {
+        self.executor.close_statement(&self.statement.sql).await
+    }
 }
 
 #[async_trait]
@@ -32,6 +105,10 @@ pub trait RbatisRef: Send {
     fn driver_type(&self) -> crate::Result<&str> {
         self.get_rbatis().driver_type()
     }
+
+    fn dialect(&self) -> crate::Result<&dyn Dialect> {
+        self.get_rbatis().dialect()
+    }
 }
 
 impl RbatisRef for Rbatis {
@@ -69,10 +146,107 @@ impl RBatisConnExecutor {
             T: DeserializeOwned,
     {
         let v = Executor::fetch(self, sql, args).await?;
-        Ok(decode(v)?)
+        decode(v)
+    }
+
+    pub async fn exec_batch(&mut self, sql: &str, args: Vec<Vec<Value>>) -> Result<Vec<ExecResult>, Error>
+    {
+        Executor::exec_batch(self, sql, args).await
+    }
+
+    /// exec sql with `:name` placeholders bound from a map or struct, see `rbdc::common::named_placeholder`
+    pub async fn exec_named<A: Serialize>(&mut self, sql: &str, arg: A) -> Result<ExecResult, Error> {
+        let (sql, args) = named_placeholder(sql, &rbs::to_value(arg)?, self.dialect()?.backslash_escapes())?;
+        self.exec(&sql, args).await
+    }
+
+    /// fetch raw Value with `:name` placeholders
+    pub async fn fetch_named<A: Serialize>(&mut self, sql: &str, arg: A) -> Result<Value, Error> {
+        let (sql, args) = named_placeholder(sql, &rbs::to_value(arg)?, self.dialect()?.backslash_escapes())?;
+        self.fetch(&sql, args).await
+    }
+
+    /// fetch and decode with `:name` placeholders
+    pub async fn fetch_decode_named<T, A: Serialize>(&mut self, sql: &str, arg: A) -> Result<T, Error>
+        where
+            T: DeserializeOwned,
+    {
+        let v = self.fetch_named(sql, arg).await?;
+        decode(v)
+    }
+
+    /// prepare a statement on this connection, the handle runs it with many args
+    pub async fn prepare(&mut self, sql: &str) -> Result<Prepared<'_>, Error> {
+        let statement = Executor::prepare_statement(self, sql).await?;
+        Ok(Prepared {
+            statement,
+            executor: self,
+        })
     }
 }
 
+/// run sql intercepts for every args of a batch, the batch is executed with the sql of the first args
+fn intercept_batch(rb: &Rbatis, sql: &str, args: &mut [Vec<Value>]) -> Result<String, Error> {
+    let mut batch_sql = None;
+    for x in args.iter_mut() {
+        let mut sql = sql.to_string();
+        plain_args(x);
+        let is_prepared = !x.is_empty();
+        for item in rb.sql_intercepts.iter() {
+            item.do_intercept(rb, &mut sql, x, is_prepared)?;
+        }
+        if batch_sql.is_none() {
+            batch_sql = Some(sql);
+        }
+    }
+    Ok(batch_sql.unwrap_or_else(|| sql.to_string()))
+}
+
+/// log and exec a batch
+async fn exec_batch_log(
+    rb: &Rbatis,
+    task_id: i64,
+    conn: &mut Box<dyn Connection>,
+    sql: &str,
+    mut args: Vec<Vec<Value>>,
+) -> Result<Vec<ExecResult>, Error> {
+    let sql = intercept_batch(rb, sql, &mut args)?;
+    if rb.log_plugin.is_enable() {
+        let (_args, args_string) = arr_to_string(args.into_iter().map(Value::Array).collect());
+        args = _args
+            .into_iter()
+            .map(|v| match v {
+                Value::Array(arr) => arr,
+                _ => vec![],
+            })
+            .collect();
+        rb.log_plugin.info(
+            task_id,
+            &format!(
+                "ExecBatch ==> {}\n{}[rbatis]                      Args   ==> {}",
+                &sql,
+                string_util::LOG_SPACE,
+                args_string
+            ),
+        );
+    }
+    let result = conn.exec_batch(&sql, args).await;
+    if rb.log_plugin.is_enable() {
+        match &result {
+            Ok(result) => {
+                let rows_affected: u64 = result.iter().map(|v| v.rows_affected).sum();
+                rb.log_plugin
+                    .info(task_id, &format!("RowsAffected <== {}", rows_affected));
+            }
+            Err(e) => {
+                rb.log_plugin
+                    .error(task_id, &format!("ReturnErr  <== {}", e));
+            }
+        }
+    }
+    result
+}
+
 fn arr_to_string(arg: Vec<Value>) -> (Vec<Value>, String) {
     let b = Value::Array(arg);
     let s = b.to_string();
@@ -87,7 +261,8 @@ impl Executor for RBatisConnExecutor {
     async fn exec(&mut self, sql: &str, mut args: Vec<Value>) -> Result<ExecResult, Error> {
         let rb_task_id = new_snowflake_id();
         let mut sql = sql.to_string();
-        let is_prepared = args.len() > 0;
+        plain_args(&mut args);
+        let is_prepared = !args.is_empty();
         for item in self.get_rbatis().sql_intercepts.iter() {
             item.do_intercept(self.get_rbatis(), &mut sql, &mut args, is_prepared)?;
         }
@@ -126,7 +301,8 @@ impl Executor for RBatisConnExecutor {
     async fn fetch(&mut self, sql: &str, mut args: Vec<Value>) -> Result<Value, Error> {
         let rb_task_id = new_snowflake_id();
         let mut sql = sql.to_string();
-        let is_prepared = args.len() > 0;
+        plain_args(&mut args);
+        let is_prepared = !args.is_empty();
         for item in self.get_rbatis().sql_intercepts.iter() {
             item.do_intercept(self.get_rbatis(), &mut sql, &mut args, is_prepared)?;
         }
@@ -160,6 +336,22 @@ impl Executor for RBatisConnExecutor {
         }
         return Ok(Value::Array(result?));
     }
+
+    async fn exec_batch(
+        &mut self,
+        sql: &str,
+        args: Vec<Vec<Value>>,
+    ) -> Result<Vec<ExecResult>, Error> {
+        exec_batch_log(&self.rb, new_snowflake_id(), &mut self.conn, sql, args).await
+    }
+
+    async fn prepare_statement(&mut self, sql: &str) -> Result<Statement, Error> {
+        self.conn.prepare(sql).await
+    }
+
+    async fn close_statement(&mut self, sql: &str) -> Result<(), Error> {
+        self.conn.close_statement(sql).await
+    }
 }
 
 impl RbatisRef for RBatisConnExecutor {
@@ -169,11 +361,11 @@ impl RbatisRef for RBatisConnExecutor {
 }
 
 impl RBatisConnExecutor {
-    pub async fn begin(self) -> crate::Result<RBatisTxExecutor> {
-        let tx = self.conn.begin().await?;
+    pub async fn begin(mut self) -> crate::Result<RBatisTxExecutor> {
+        self.conn.exec(self.rb.dialect()?.begin(), vec![]).await?;
         return Ok(RBatisTxExecutor {
             tx_id: new_snowflake_id(),
-            conn: tx,
+            conn: self.conn,
             rb: self.rb,
             done: false,
         });
@@ -215,7 +407,42 @@ impl<'a> RBatisTxExecutor {
             T: DeserializeOwned,
     {
         let v = Executor::fetch(self, sql, args).await?;
-        Ok(decode(v)?)
+        decode(v)
+    }
+    /// exec one sql with many args
+    pub async fn exec_batch(&mut self, sql: &str, args: Vec<Vec<Value>>) -> Result<Vec<ExecResult>, Error>
+    {
+        Executor::exec_batch(self, sql, args).await
+    }
+
+    /// exec sql with `:name` placeholders bound from a map or struct, see `rbdc::common::named_placeholder`
+    pub async fn exec_named<A: Serialize>(&mut self, sql: &str, arg: A) -> Result<ExecResult, Error> {
+        let (sql, args) = named_placeholder(sql, &rbs::to_value(arg)?, self.dialect()?.backslash_escapes())?;
+        self.exec(&sql, args).await
+    }
+
+    /// fetch raw Value with `:name` placeholders
+    pub async fn fetch_named<A: Serialize>(&mut self, sql: &str, arg: A) -> Result<Value, Error> {
+        let (sql, args) = named_placeholder(sql, &rbs::to_value(arg)?, self.dialect()?.backslash_escapes())?;
+        self.fetch(&sql, args).await
+    }
+
+    /// fetch and decode with `:name` placeholders
+    pub async fn fetch_decode_named<T, A: Serialize>(&mut self, sql: &str, arg: A) -> Result<T, Error>
+        where
+            T: DeserializeOwned,
+    {
+        let v = self.fetch_named(sql, arg).await?;
+        decode(v)
+    }
+
+    /// prepare a statement on this connection, the handle runs it with many args
+    pub async fn prepare(&mut self, sql: &str) -> Result<Prepared<'_>, Error> {
+        let statement = Executor::prepare_statement(self, sql).await?;
+        Ok(Prepared {
+            statement,
+            executor: self,
+        })
     }
 }
 
@@ -227,7 +454,8 @@ impl Executor for RBatisTxExecutor {
         mut args: Vec<Value>,
     ) -> Result<rbdc::db::ExecResult, Error> {
         let mut sql = sql.to_string();
-        let is_prepared = args.len() > 0;
+        plain_args(&mut args);
+        let is_prepared = !args.is_empty();
         for item in self.get_rbatis().sql_intercepts.iter() {
             item.do_intercept(self.get_rbatis(), &mut sql, &mut args, is_prepared)?;
         }
@@ -265,7 +493,8 @@ impl Executor for RBatisTxExecutor {
 
     async fn fetch(&mut self, sql: &str, mut args: Vec<Value>) -> Result<Value, Error> {
         let mut sql = sql.to_string();
-        let is_prepared = args.len() > 0;
+        plain_args(&mut args);
+        let is_prepared = !args.is_empty();
         for item in self.get_rbatis().sql_intercepts.iter() {
             item.do_intercept(self.get_rbatis(), &mut sql, &mut args, is_prepared)?;
         }
@@ -299,6 +528,26 @@ impl Executor for RBatisTxExecutor {
         }
         return Ok(Value::Array(result?));
     }
+
+    async fn exec_batch(
+        &mut self,
+        sql: &str,
+        args: Vec<Vec<Value>>,
+    ) -> Result<Vec<ExecResult>, Error> {
+        exec_batch_log(&self.rb, self.tx_id, &mut self.conn, sql, args).await
+    }
+
+    async fn prepare_statement(&mut self, sql: &str) -> Result<Statement, Error> {
+        self.conn.prepare(sql).await
+    }
+
+    async fn close_statement(&mut self, sql: &str) -> Result<(), Error> {
+        self.conn.close_statement(sql).await
+    }
+
+    fn in_transaction(&self) -> bool {
+        !self.done
+    }
 }
 
 impl RbatisRef for RBatisTxExecutor {
@@ -309,17 +558,17 @@ impl RbatisRef for RBatisTxExecutor {
 
 impl RBatisTxExecutor {
     pub async fn begin(mut self) -> crate::Result<Self> {
-        self.conn = self.conn.begin().await?;
+        self.conn.exec(self.rb.dialect()?.begin(), vec![]).await?;
         return Ok(self);
     }
     pub async fn commit(&mut self) -> crate::Result<bool> {
-        if let Ok(()) = self.conn.commit().await {
+        if self.conn.exec(self.rb.dialect()?.commit(), vec![]).await.is_ok() {
             self.done = true;
         }
         return Ok(self.done);
     }
     pub async fn rollback(&mut self) -> crate::Result<bool> {
-        if let Ok(()) = self.conn.rollback().await {
+        if self.conn.exec(self.rb.dialect()?.rollback(), vec![]).await.is_ok() {
             self.done = true;
         }
         return Ok(self.done);
@@ -479,6 +728,43 @@ impl Executor for RBatisTxExecutorGuard{
             }
         }
     }
+
+    async fn exec_batch(&mut self, sql: &str, args: Vec<Vec<Value>>) -> Result<Vec<ExecResult>, Error> {
+        match self.tx.as_mut(){
+            None => {
+                Err(Error::from("the tx is done!"))
+            }
+            Some(v) => {
+                v.exec_batch(sql,args).await
+            }
+        }
+    }
+
+    async fn prepare_statement(&mut self, sql: &str) -> Result<Statement, Error> {
+        match self.tx.as_mut(){
+            None => {
+                Err(Error::from("the tx is done!"))
+            }
+            Some(v) => {
+                v.prepare_statement(sql).await
+            }
+        }
+    }
+
+    async fn close_statement(&mut self, sql: &str) -> Result<(), Error> {
+        match self.tx.as_mut(){
+            None => {
+                Err(Error::from("the tx is done!"))
+            }
+            Some(v) => {
+                v.close_statement(sql).await
+            }
+        }
+    }
+
+    fn in_transaction(&self) -> bool {
+        self.tx.as_ref().map(|v| v.in_transaction()).unwrap_or_default()
+    }
 }
 
 impl Rbatis {
@@ -502,7 +788,34 @@ impl Rbatis {
     {
         let mut conn = self.acquire().await?;
         let v = conn.fetch(sql, args).await?;
-        Ok(decode(v)?)
+        decode(v)
+    }
+
+    /// exec one sql with many args in a single pipeline
+    pub async fn exec_batch(&self, sql: &str, args: Vec<Vec<Value>>) -> Result<Vec<ExecResult>, Error> {
+        let mut conn = self.acquire().await?;
+        conn.exec_batch(sql, args).await
+    }
+
+    /// exec sql with `:name` placeholders bound from a map or struct, see `rbdc::common::named_placeholder`
+    pub async fn exec_named<A: Serialize>(&self, sql: &str, arg: A) -> Result<ExecResult, Error> {
+        let (sql, args) = named_placeholder(sql, &rbs::to_value(arg)?, self.dialect()?.backslash_escapes())?;
+        self.exec(&sql, args).await
+    }
+
+    /// fetch raw Value with `:name` placeholders
+    pub async fn fetch_named<A: Serialize>(&self, sql: &str, arg: A) -> Result<Value, Error> {
+        let (sql, args) = named_placeholder(sql, &rbs::to_value(arg)?, self.dialect()?.backslash_escapes())?;
+        self.fetch(&sql, args).await
+    }
+
+    /// fetch and decode with `:name` placeholders
+    pub async fn fetch_decode_named<T, A: Serialize>(&self, sql: &str, arg: A) -> Result<T, Error>
+        where
+            T: DeserializeOwned,
+    {
+        let v = self.fetch_named(sql, arg).await?;
+        decode(v)
     }
 }
 
@@ -517,6 +830,11 @@ impl Executor for Rbatis {
         let mut conn = self.acquire().await?;
         conn.fetch(sql, args).await
     }
+
+    async fn exec_batch(&mut self, sql: &str, args: Vec<Vec<Value>>) -> Result<Vec<ExecResult>, Error> {
+        let mut conn = self.acquire().await?;
+        conn.exec_batch(sql, args).await
+    }
 }
 
 impl RbatisRef for &Rbatis {
@@ -536,4 +854,48 @@ impl Executor for &Rbatis {
         let mut conn = self.acquire().await?;
         conn.fetch(sql, args).await
     }
+
+    async fn exec_batch(&mut self, sql: &str, args: Vec<Vec<Value>>) -> Result<Vec<ExecResult>, Error> {
+        let mut conn = self.acquire().await?;
+        conn.exec_batch(sql, args).await
+    }
+}
+
+/// an executor recording the sql and args of the last `exec`/`fetch` instead of running it,
+/// `fetch` returns no row. used to render the sql of a `#[py_sql]` fn and wrap it
+pub struct SqlCapture<'a> {
+    pub rb: &'a Rbatis,
+    pub sql: String,
+    pub args: Vec<Value>,
+}
+
+impl<'a> SqlCapture<'a> {
+    pub fn new(rb: &'a Rbatis) -> Self {
+        Self {
+            rb,
+            sql: String::new(),
+            args: vec![],
+        }
+    }
+}
+
+impl RbatisRef for SqlCapture<'_> {
+    fn get_rbatis(&self) -> &Rbatis {
+        self.rb
+    }
+}
+
+#[async_trait]
+impl Executor for SqlCapture<'_> {
+    async fn exec(&mut self, sql: &str, args: Vec<Value>) -> Result<ExecResult, Error> {
+        self.sql = sql.to_string();
+        self.args = args;
+        Ok(ExecResult::default())
+    }
+
+    async fn fetch(&mut self, sql: &str, args: Vec<Value>) -> Result<Value, Error> {
+        self.sql = sql.to_string();
+        self.args = args;
+        Ok(Value::Array(vec![]))
+    }
 }
@@ -12,7 +12,7 @@ extern crate rbatis_macro_driver;
 
 extern crate flume;
 
-pub use rbatis_macro_driver::{html_sql, py_sql, sql};
+pub use rbatis_macro_driver::{html_sql, py_sql, sql, CRUDTable};
 
 
 pub mod plugin;
@@ -26,6 +26,7 @@ pub mod executor;
 pub mod sql;
 #[macro_use]
 pub mod crud;
+pub mod table;
 #[macro_use]
 pub mod error;
 pub mod decode;
@@ -8,14 +8,20 @@ use std::collections::HashMap;
 use std::ops::{Deref, DerefMut};
 use serde::Serialize;
 use rbs::{to_value, Value};
+use rbs::value::map::ValueMap;
 use crate::Error;
 use rbdc::db::Connection;
 use crate::executor::RBatisConnExecutor;
 use crate::utils::string_util::to_snake_name;
 
 #[async_trait::async_trait]
-pub trait TableSync {
+pub trait TableSync: Send + Sync {
     async fn sync(&self, rb: RBatisConnExecutor, table: Value, name: &str) -> Result<(), Error>;
+
+    /// sync with the primary key columns, more keys make a composite primary key
+    async fn sync_with_keys(&self, rb: RBatisConnExecutor, table: Value, name: &str, keys: &[&str]) -> Result<(), Error> {
+        self.sync(rb, table, name).await
+    }
 }
 
 pub struct RbatisTableSync {
@@ -69,4 +75,29 @@ impl RbatisTableSync {
             }
         }
     }
+    pub async fn sync_with_keys<Table: Serialize + Any>(&self, driver_type: &str, rb: RBatisConnExecutor, table: Table, table_name: &str, keys: &[&str]) -> Result<(), Error> {
+        let plugin = self.plugins.get(driver_type);
+        match plugin {
+            None => {
+                Err(Error::from("not support or load plugin!"))
+            }
+            Some(plugin) => {
+                log::info!("sync table_name:{},keys:{:?}",table_name,keys);
+                plugin.sync_with_keys(rb, to_value!(table), table_name, keys).await
+            }
+        }
+    }
+    /// sync a `#[derive(CRUDTable)]` table, with its column names and `#[column(id)]` keys
+    pub async fn sync_table<T: crate::table::Table + Serialize>(&self, driver_type: &str, rb: RBatisConnExecutor, table: T) -> Result<(), Error> {
+        let table = to_value!(table);
+        let mut columns = ValueMap::new();
+        let mut keys = vec![];
+        for c in T::table_columns() {
+            columns.insert(Value::String(c.name.to_string()), table[c.field].clone());
+            if c.id {
+                keys.push(c.name);
+            }
+        }
+        self.sync_with_keys(driver_type, rb, Value::Map(columns), T::table_name(), &keys).await
+    }
 }
@@ -36,9 +36,29 @@ fn type_str(v: &Value) -> &'static str {
 
 #[async_trait::async_trait]
 impl TableSync for SqliteTableSync {
-    async fn sync(&self, mut rb: RBatisConnExecutor, table: Value, name: &str) -> Result<(), Error> {
+    async fn sync(&self, rb: RBatisConnExecutor, table: Value, name: &str) -> Result<(), Error> {
+        let mut keys = vec!["id".to_string()];
+        if let Value::Map(m) = &table {
+            for (k, v) in m {
+                if v.as_str().unwrap_or_default() == "id" {
+                    keys.push(k.as_str().unwrap_or_default().to_string());
+                }
+            }
+        }
+        let keys: Vec<&str> = keys.iter().map(|k| k.as_str()).collect();
+        self.sync_with_keys(rb, table, name, &keys).await
+    }
+
+    async fn sync_with_keys(&self, mut rb: RBatisConnExecutor, table: Value, name: &str, keys: &[&str]) -> Result<(), Error> {
         match table {
             Value::Map(m) => {
+                let mut table_keys = vec![];
+                for (k, _) in &m {
+                    let k = k.as_str().unwrap_or_default();
+                    if keys.contains(&k) {
+                        table_keys.push(k);
+                    }
+                }
                 let mut sql_create = format!("CREATE TABLE {} ", name);
                 let mut sql_column = format!("");
                 for (k, v) in &m {
@@ -46,11 +66,16 @@ impl TableSync for SqliteTableSync {
                     sql_column.push_str(k);
                     sql_column.push_str(" ");
                     sql_column.push_str(type_str(&v));
-                    if k.eq("id") || v.as_str().unwrap_or_default() == "id" {
+                    if table_keys.len() == 1 && table_keys.contains(&k) {
                         sql_column.push_str(" PRIMARY KEY NOT NULL ");
+                    } else if table_keys.contains(&k) {
+                        sql_column.push_str(" NOT NULL ");
                     }
                     sql_column.push_str(",");
                 }
+                if table_keys.len() > 1 {
+                    sql_column.push_str(&format!("PRIMARY KEY ({})", table_keys.join(",")));
+                }
                 if sql_column.ends_with(",") {
                     sql_column = sql_column.trim_end_matches(",").to_string();
                 }
@@ -63,7 +88,7 @@ impl TableSync for SqliteTableSync {
                             for (k, v) in &m {
                                 let k = k.as_str().unwrap_or_default();
                                 let mut id_key = "";
-                                if k.eq("id") || v.as_str().unwrap_or_default() == "id" {
+                                if table_keys.len() == 1 && table_keys.contains(&k) {
                                     id_key = " PRIMARY KEY NOT NULL";
                                 }
                                 match rb.exec(&format!("alter table {} add {} {} {};", name, k, type_str(&v), id_key), vec![]).await {
@@ -84,7 +109,7 @@ impl TableSync for SqliteTableSync {
                 Ok(())
             }
             Value::Ext(table_name, m) => {
-                self.sync(rb, *m, name).await
+                self.sync_with_keys(rb, *m, name, keys).await
             }
             _ => {
                 Err(Error::from("table not is an struct or map!"))
@@ -0,0 +1,222 @@
+use rbdc::dialect::Dialect;
+use rbs::Value;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::decode::decode;
+use crate::executor::Executor;
+use crate::sql::page::DEFAULT_PAGE_SIZE;
+use crate::Error;
+
+/// a keyset page request, the page after or before the `cursor` of the last page
+#[derive(Serialize, Deserialize, Clone, Debug, Default, Eq, PartialEq)]
+pub struct CursorRequest {
+    /// `next_cursor` or `prev_cursor` of the last page, none for the first page
+    pub cursor: Option<String>,
+    /// default 10
+    pub page_size: u64,
+}
+
+impl CursorRequest {
+    pub fn new(page_size: u64) -> Self {
+        Self {
+            cursor: None,
+            page_size,
+        }
+    }
+
+    pub fn with_cursor(cursor: &str, page_size: u64) -> Self {
+        Self {
+            cursor: Some(cursor.to_string()),
+            page_size,
+        }
+    }
+}
+
+/// a keyset page, the cursors are opaque tokens for `CursorRequest::cursor`
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct CursorPage<T> {
+    pub records: Vec<T>,
+    pub page_size: u64,
+    /// the page after, none on the last page
+    pub next_cursor: Option<String>,
+    /// the page before, none on the first page
+    pub prev_cursor: Option<String>,
+}
+
+impl<T> Default for CursorPage<T> {
+    fn default() -> Self {
+        Self {
+            records: vec![],
+            page_size: DEFAULT_PAGE_SIZE,
+            next_cursor: None,
+            prev_cursor: None,
+        }
+    }
+}
+
+// hex of the json `[forward, [key values]]`
+fn encode_cursor(forward: bool, key: Vec<Value>) -> Result<String, Error> {
+    let bytes = serde_json::to_vec(&(forward, key)).map_err(|e| Error::from(e.to_string()))?;
+    Ok(hex::encode(bytes))
+}
+
+fn decode_cursor(cursor: &str, keys: usize) -> Result<(bool, Vec<Value>), Error> {
+    let invalid = || Error::from(format!("[rbatis] invalid cursor '{}'", cursor));
+    let bytes = hex::decode(cursor).map_err(|_| invalid())?;
+    let (forward, key): (bool, Vec<Value>) = serde_json::from_slice(&bytes).map_err(|_| invalid())?;
+    if key.len() != keys {
+        return Err(invalid());
+    }
+    // json keeps no integer type, a key of the i64 range is an i64
+    let key = key
+        .into_iter()
+        .map(|v| match v {
+            Value::U64(v) if v <= i64::MAX as u64 => Value::I64(v as i64),
+            v => v,
+        })
+        .collect();
+    Ok((forward, key))
+}
+
+/// `(a, b) > (?, ?)`, or `(a > ? or (a = ? and b > ?))` if not `dialect.tuple_compare()`.
+/// `forward` is `>`, else `<`
+pub fn keyset_sql(
+    dialect: &dyn Dialect,
+    keys: &[&str],
+    values: Vec<Value>,
+    forward: bool,
+) -> (String, Vec<Value>) {
+    let op = if forward { ">" } else { "<" };
+    let quoted: Vec<String> = keys.iter().map(|k| dialect.quote(k)).collect();
+    if dialect.tuple_compare() || keys.len() == 1 {
+        let sql = format!(
+            "({}) {} ({})",
+            quoted.join(","),
+            op,
+            vec!["?"; keys.len()].join(",")
+        );
+        return (sql, values);
+    }
+    let mut terms = vec![];
+    let mut args = vec![];
+    for (i, (k, v)) in quoted.iter().zip(&values).enumerate() {
+        let mut term = vec![];
+        for (eq_k, eq_v) in quoted.iter().zip(&values).take(i) {
+            term.push(format!("{} = ?", eq_k));
+            args.push(eq_v.clone());
+        }
+        term.push(format!("{} {} ?", k, op));
+        args.push(v.clone());
+        terms.push(format!("({})", term.join(" and ")));
+    }
+    (format!("({})", terms.join(" or ")), args)
+}
+
+/// fetch a keyset page of the rows of a select ordered by the unique `keys`:
+///
+/// `select * from (sql) t_cursor where (a, b) > (?, ?) order by a, b limit page_size + 1`
+pub async fn select_cursor<T: DeserializeOwned>(
+    rb: &mut dyn Executor,
+    req: &CursorRequest,
+    keys: &[&str],
+    sql: &str,
+    mut args: Vec<Value>,
+) -> Result<CursorPage<T>, Error> {
+    if keys.is_empty() {
+        return Err(Error::from("[rbatis] a cursor page needs the key columns!"));
+    }
+    let page_size = if req.page_size == 0 {
+        DEFAULT_PAGE_SIZE
+    } else {
+        req.page_size
+    };
+    let (forward, cursor) = match &req.cursor {
+        None => (true, None),
+        Some(c) => {
+            let (forward, key) = decode_cursor(c, keys.len())?;
+            (forward, Some(key))
+        }
+    };
+    let dialect = rb.dialect()?;
+    let mut sql = format!("select * from ({}) t_cursor", sql);
+    if let Some(key) = cursor.clone() {
+        let (keyset, keyset_args) = keyset_sql(dialect, keys, key, forward);
+        sql.push_str(" where ");
+        sql.push_str(&keyset);
+        args.extend(keyset_args);
+    }
+    let order: Vec<String> = keys
+        .iter()
+        .map(|k| format!("{} {}", dialect.quote(k), if forward { "asc" } else { "desc" }))
+        .collect();
+    sql.push_str(" order by ");
+    sql.push_str(&order.join(","));
+    sql.push(' ');
+    sql.push_str(&dialect.page_limit(0, page_size + 1, true));
+    let mut rows = match rb.fetch(&sql, args).await? {
+        Value::Array(rows) => rows,
+        _ => vec![],
+    };
+    let more = rows.len() as u64 > page_size;
+    rows.truncate(page_size as usize);
+    if !forward {
+        rows.reverse();
+    }
+    let key_of = |row: &Value| keys.iter().map(|k| row[*k].clone()).collect::<Vec<Value>>();
+    let mut page = CursorPage {
+        records: vec![],
+        page_size,
+        next_cursor: None,
+        prev_cursor: None,
+    };
+    if let (Some(first), Some(last)) = (rows.first(), rows.last()) {
+        // the page before exists if this page was reached by a cursor going forward
+        if (forward && cursor.is_some()) || (!forward && more) {
+            page.prev_cursor = Some(encode_cursor(false, key_of(first))?);
+        }
+        if more || !forward {
+            page.next_cursor = Some(encode_cursor(true, key_of(last))?);
+        }
+    }
+    page.records = decode(Value::Array(rows))?;
+    Ok(page)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::sql::cursor::{decode_cursor, encode_cursor, keyset_sql};
+    use rbdc::dialect::{DefaultDialect, Dialect};
+    use rbs::Value;
+
+    #[derive(Debug)]
+    struct ExpandDialect {}
+
+    impl Dialect for ExpandDialect {
+        fn tuple_compare(&self) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn test_keyset_sql() {
+        let values = vec![Value::I64(1), Value::I64(2)];
+        let (sql, args) = keyset_sql(&DefaultDialect {}, &["a", "b"], values.clone(), true);
+        assert_eq!(sql, "(a,b) > (?,?)");
+        assert_eq!(args.len(), 2);
+        let (sql, args) = keyset_sql(&ExpandDialect {}, &["a", "b"], values, false);
+        assert_eq!(sql, "((a < ?) or (a = ? and b < ?))");
+        assert_eq!(args, vec![Value::I64(1), Value::I64(1), Value::I64(2)]);
+    }
+
+    #[test]
+    fn test_cursor_token() {
+        let c = encode_cursor(false, vec![Value::I64(1), Value::from("a")]).unwrap();
+        assert_eq!(
+            decode_cursor(&c, 2).unwrap(),
+            (false, vec![Value::I64(1), Value::from("a")])
+        );
+        assert!(decode_cursor(&c, 1).is_err());
+        assert!(decode_cursor("zz", 1).is_err());
+    }
+}
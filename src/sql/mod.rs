@@ -1,5 +1,9 @@
 pub mod page;
 pub use page::*;
+pub mod cursor;
+pub use cursor::*;
 pub mod template;
 pub mod tx;
+pub mod wrapper;
+pub use wrapper::*;
 pub use template::TEMPLATE;
@@ -1,9 +1,17 @@
 use std::fmt::{Debug, Display, Formatter};
 use std::future::Future;
+use std::str::FromStr;
 
 use futures_core::future::BoxFuture;
 use serde::de::DeserializeOwned;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::decode::decode;
+use crate::executor::Executor;
+use crate::Error;
+use rbdc::common::{has_order_by, strip_order_limit};
+use rbdc::dialect::Dialect;
+use rbs::Value;
 
 /// default 10
 pub const DEFAULT_PAGE_SIZE: u64 = 10;
@@ -64,6 +72,95 @@ pub struct Page<T> {
     pub search_count: bool,
 }
 
+/// the direction of an `OrderBy`
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Order {
+    #[default]
+    Asc,
+    Desc,
+}
+
+/// a sort column of a `PageRequest`, serialized as `"create_time desc"`.
+///
+/// it is deserialized from `"create_time"`, `"create_time desc"` or `{"column":"create_time","order":"desc"}`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OrderBy {
+    pub column: String,
+    pub order: Order,
+}
+
+impl FromStr for OrderBy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut words = s.split_whitespace();
+        let column = words.next().unwrap_or_default().to_string();
+        let order = match words.next().map(|o| o.to_lowercase()).as_deref() {
+            None | Some("asc") => Order::Asc,
+            Some("desc") => Order::Desc,
+            Some(o) => return Err(Error::from(format!("[rbatis] unknown order '{}', use asc or desc", o))),
+        };
+        if column.is_empty() || words.next().is_some() {
+            return Err(Error::from(format!("[rbatis] invalid order by '{}'", s)));
+        }
+        Ok(OrderBy { column, order })
+    }
+}
+
+impl Display for OrderBy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.order {
+            Order::Asc => write!(f, "{} asc", self.column),
+            Order::Desc => write!(f, "{} desc", self.column),
+        }
+    }
+}
+
+impl Serialize for OrderBy {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for OrderBy {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Str(String),
+            Map {
+                column: String,
+                #[serde(default)]
+                order: Order,
+            },
+        }
+        match Repr::deserialize(deserializer)? {
+            Repr::Str(s) => s.parse().map_err(serde::de::Error::custom),
+            Repr::Map { column, order } => Ok(OrderBy { column, order }),
+        }
+    }
+}
+
+// a list of `OrderBy` or the string `"create_time desc,id"` of a query string
+fn deserialize_order_by<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<OrderBy>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Repr {
+        Str(String),
+        List(Vec<OrderBy>),
+    }
+    match Option::<Repr>::deserialize(deserializer)? {
+        None => Ok(vec![]),
+        Some(Repr::List(list)) => Ok(list),
+        Some(Repr::Str(s)) => s
+            .split(',')
+            .filter(|c| !c.trim().is_empty())
+            .map(|c| c.parse().map_err(serde::de::Error::custom))
+            .collect(),
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
 pub struct PageRequest {
     /// total num
@@ -72,7 +169,15 @@ pub struct PageRequest {
     pub page_no: u64,
     /// page page_size default 10
     pub page_size: u64,
+    /// false skips the count query, the page keeps `total`
     pub search_count: bool,
+    /// count the total with `count(*) over()` in the select of the records if the dialect supports it,
+    /// not of the `#[py_sql]` and `#[html_sql]` fns
+    #[serde(default)]
+    pub count_over: bool,
+    /// the sort of the page, only columns the page allows
+    #[serde(default, deserialize_with = "deserialize_order_by")]
+    pub order_by: Vec<OrderBy>,
 }
 
 impl PageRequest {
@@ -98,8 +203,49 @@ impl PageRequest {
             page_size,
             page_no: page_no,
             search_count: true,
+            count_over: false,
+            order_by: vec![],
         };
     }
+
+    /// one query for the records and the total, see `Dialect::count_over()`
+    pub fn set_count_over(mut self, arg: bool) -> Self {
+        self.count_over = arg;
+        self
+    }
+
+    /// sort by the columns, `PageRequest::new(1, 10).order_by(vec![("create_time", Order::Desc)])`
+    pub fn order_by<C: AsRef<str>>(mut self, columns: Vec<(C, Order)>) -> Self {
+        for (column, order) in columns {
+            self.order_by.push(OrderBy {
+                column: column.as_ref().to_string(),
+                order,
+            });
+        }
+        self
+    }
+
+    /// `order by "a" desc,"b" asc` of `order_by`, empty if there is none.
+    /// an error if a column is not one of the `sortable`
+    pub fn order_sql(&self, dialect: &dyn Dialect, sortable: &[&str]) -> Result<String, Error> {
+        let mut columns = Vec::with_capacity(self.order_by.len());
+        for o in &self.order_by {
+            if !sortable.contains(&o.column.as_str()) {
+                return Err(Error::from(format!(
+                    "[rbatis] can not sort by '{}', the sortable columns are {:?}",
+                    o.column, sortable
+                )));
+            }
+            columns.push(match o.order {
+                Order::Asc => format!("{} asc", dialect.quote(&o.column)),
+                Order::Desc => format!("{} desc", dialect.quote(&o.column)),
+            });
+        }
+        if columns.is_empty() {
+            return Ok(String::new());
+        }
+        Ok(format!("order by {}", columns.join(",")))
+    }
 }
 
 impl Default for PageRequest {
@@ -109,6 +255,8 @@ impl Default for PageRequest {
             page_size: DEFAULT_PAGE_SIZE,
             page_no: 1,
             search_count: true,
+            count_over: false,
+            order_by: vec![],
         };
     }
 }
@@ -297,9 +445,143 @@ impl<V> Page<V> {
     }
 }
 
+impl<T: DeserializeOwned> Page<T> {
+    /// the page of the fetched `rows` of a request
+    pub fn from_rows(page_req: &PageRequest, total: u64, rows: Value) -> Result<Self, Error> {
+        let mut page = Page::<T>::new_total(page_req.page_no, page_req.page_size, total);
+        page.search_count = page_req.search_count;
+        page.records = decode(rows)?;
+        Ok(page)
+    }
+}
+
+/// the sqls of a page of a rendered select, see `page_sql`
+#[derive(Clone, Debug, PartialEq)]
+pub struct PageSql {
+    /// the select of the records with `dialect.page_limit()`
+    pub sql: String,
+    pub args: Vec<Value>,
+    /// the count of the select and its args, none if not `page_req.search_count`
+    pub count: Option<(String, Vec<Value>)>,
+}
+
+/// the sqls of a page of a rendered select, `#[py_sql]` and `#[html_sql]` fns returning `Page<T>` fetch them.
+///
+/// the total is a `select count(1) as count from (..) t_count` of the select without its `order by` and limit.
+/// `page_req.order_by` of the `sortable` columns, `#[py_sql(.., sortable = "a,b")]`, replaces the `order by`
+/// and limit of the select, it is ignored without `sortable` as the select may have its own order.
+/// `page_req.count_over` is ignored, the select list of a mapper is not rewritten
+pub fn page_sql(
+    dialect: &dyn Dialect,
+    page_req: &PageRequest,
+    sortable: &[&str],
+    sql: &str,
+    mut args: Vec<Value>,
+) -> Result<PageSql, Error> {
+    let mut sql = sql.trim_end().to_string();
+    if !sortable.is_empty() {
+        let order_sql = page_req.order_sql(dialect, sortable)?;
+        if !order_sql.is_empty() {
+            let (stripped, removed) = strip_order_limit(&sql, dialect.backslash_escapes());
+            args.truncate(args.len().saturating_sub(removed));
+            sql = format!("{} {}", stripped, order_sql);
+        }
+    }
+    let count = if page_req.search_count {
+        let (stripped, removed) = strip_order_limit(&sql, dialect.backslash_escapes());
+        let count_args = args[..args.len().saturating_sub(removed)].to_vec();
+        Some((format!("select count(1) as count from ({}) t_count", stripped), count_args))
+    } else {
+        None
+    };
+    let ordered = has_order_by(&sql, dialect.backslash_escapes());
+    sql.push(' ');
+    sql.push_str(&dialect.page_limit(page_req.offset(), page_req.page_size, ordered));
+    Ok(PageSql { sql, args, count })
+}
+
+/// fetch a page of `select {columns} from {table}{tail}`, `tail` is the `where ..` of the `args`.
+///
+/// `paged` is true if `tail` already has its limit, else `dialect.page_limit()` is appended.
+/// the `page_req.order_by` of the `sortable` columns replaces the `order by` and limit of the `tail`.
+/// the total is a `select count(1)` of the `tail` without its `order by` and limit,
+/// no query if not `page_req.search_count`, or a `count(*) over()` of the records if `page_req.count_over`
+#[allow(clippy::too_many_arguments)]
+pub async fn select_page<T: DeserializeOwned>(
+    rb: &mut dyn Executor,
+    page_req: &PageRequest,
+    columns: &str,
+    table: &str,
+    sortable: &[&str],
+    tail: &str,
+    mut args: Vec<Value>,
+    mut paged: bool,
+) -> Result<Page<T>, Error> {
+    let dialect = rb.dialect()?;
+    let backslash = dialect.backslash_escapes();
+    let order_sql = page_req.order_sql(dialect, sortable)?;
+    let mut tail = tail.to_string();
+    if !order_sql.is_empty() {
+        let (stripped, removed) = strip_order_limit(&tail, backslash);
+        args.truncate(args.len().saturating_sub(removed));
+        tail = format!("{} {}", stripped, order_sql);
+        paged = false;
+    }
+    let tail = tail.as_str();
+    let count_over = page_req.search_count && page_req.count_over && dialect.count_over();
+    let mut sql = if count_over {
+        // oracle needs a qualified `*` next to other columns
+        let columns = if columns == "*" {
+            format!("{}.*", table)
+        } else {
+            columns.to_string()
+        };
+        format!("select {}, count(*) over() as rb_count from {}{}", columns, table, tail)
+    } else {
+        format!("select {} from {}{}", columns, table, tail)
+    };
+    if !paged {
+        let ordered = has_order_by(tail, backslash);
+        sql.push(' ');
+        sql.push_str(&dialect.page_limit(page_req.offset(), page_req.page_size, ordered));
+    }
+    let mut rows = match rb.fetch(&sql, args.clone()).await? {
+        Value::Array(rows) => rows,
+        _ => vec![],
+    };
+    let mut total = None;
+    if count_over {
+        let rb_count = Value::from("rb_count");
+        for row in rows.iter_mut() {
+            if let Value::Map(m) = row {
+                if let Some(v) = m.remove(&rb_count) {
+                    total = total.or(v.as_u64());
+                }
+            }
+        }
+        // an empty page of the first offset has no row at all
+        if rows.is_empty() && page_req.offset() == 0 {
+            total = Some(0);
+        }
+    }
+    let total = match total {
+        Some(total) => total,
+        None if !page_req.search_count => page_req.total,
+        None => {
+            let (tail, removed) = strip_order_limit(tail, backslash);
+            args.truncate(args.len().saturating_sub(removed));
+            let count_sql = format!("select count(1) as count from {}{}", table, tail);
+            decode(rb.fetch(&count_sql, args).await?)?
+        }
+    };
+    Page::from_rows(page_req, total, Value::Array(rows))
+}
+
 #[cfg(test)]
 mod test {
-    use crate::sql::page::Page;
+    use crate::sql::page::{page_sql, IPageRequest, Order, Page, PageRequest};
+    use rbdc::dialect::DefaultDialect;
+    use rbs::Value;
 
     #[test]
     fn test_page() {
@@ -308,4 +590,23 @@ mod test {
         println!("{:?}", page);
         assert_eq!(page.pages, 1);
     }
+
+    #[test]
+    fn test_page_sql() {
+        let args = vec![Value::from("a")];
+        let sql = "select * from t where name = ? order by id ";
+        let page = page_sql(&DefaultDialect {}, &PageRequest::new(2, 10), &[], sql, args.clone()).unwrap();
+        assert_eq!(page.sql, "select * from t where name = ? order by id limit 10,10");
+        let (count_sql, count_args) = page.count.unwrap();
+        assert_eq!(count_sql, "select count(1) as count from (select * from t where name = ?) t_count");
+        assert_eq!(count_args, vec![Value::from("a")]);
+        let req = PageRequest::new(1, 10).set_search_count(false).order_by(vec![("name", Order::Desc)]);
+        // no sortable keeps the order of the select
+        let page = page_sql(&DefaultDialect {}, &req, &[], sql, args.clone()).unwrap();
+        assert!(page.count.is_none());
+        assert_eq!(page.sql, "select * from t where name = ? order by id limit 0,10");
+        let page = page_sql(&DefaultDialect {}, &req, &["name"], sql, args.clone()).unwrap();
+        assert_eq!(page.sql, "select * from t where name = ? order by name desc limit 0,10");
+        assert!(page_sql(&DefaultDialect {}, &req, &["id"], sql, args).is_err());
+    }
 }
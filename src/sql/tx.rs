@@ -3,6 +3,8 @@ use futures_core::future::BoxFuture;
 use rbdc::db::Connection;
 use crate::Error;
 
+/// transaction statements of the default dialect,
+/// `RBatisTxExecutor` sends the statements of the linked driver's dialect instead
 #[async_trait]
 pub trait Tx {
     async fn begin(mut self) -> Result<Self, Error>
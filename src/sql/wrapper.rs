@@ -0,0 +1,460 @@
+use rbdc::dialect::Dialect;
+use rbs::Value;
+use serde::Serialize;
+use std::fmt::{Debug, Formatter};
+use std::marker::PhantomData;
+
+use crate::table::Column;
+use crate::Error;
+
+/// a condition of a `QueryWrapper`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    /// `column op ?`
+    Compare(String, &'static str, Value),
+    /// `column in (?,?)` or `column not in (?,?)`
+    In(String, bool, Vec<Value>),
+    /// `column between ? and ?`
+    Between(String, Value, Value),
+    /// `column is null` or `column is not null`
+    IsNull(String, bool),
+    /// `(..)` of a nested wrapper
+    Group(QueryWrapper),
+}
+
+/// a column of a `QueryWrapper<T>`, a `Column<T, _>` of the table `T` such as `BizActivity::name()`,
+/// or a `&str`/`String` identifier of the untyped `QueryWrapper` of the `crud!` tables
+pub trait WrapperColumn<T> {
+    fn column_name(&self) -> &str;
+}
+
+impl<T, V> WrapperColumn<T> for Column<T, V> {
+    fn column_name(&self) -> &str {
+        self.name
+    }
+}
+
+impl WrapperColumn<()> for &str {
+    fn column_name(&self) -> &str {
+        self
+    }
+}
+
+impl WrapperColumn<()> for String {
+    fn column_name(&self) -> &str {
+        self
+    }
+}
+
+impl WrapperColumn<()> for &String {
+    fn column_name(&self) -> &str {
+        self
+    }
+}
+
+/// a runtime condition builder of the `where`, `group by`, `order by` and `limit` of a query.
+///
+/// values are bound as `?` args, columns are quoted per dialect.
+/// a `QueryWrapper<T>` of a `#[derive(CRUDTable)]` table only takes its `Column`s, so a misspelled
+/// column or one of another table is a compile error:
+/// ```rust
+/// use rbatis::sql::QueryWrapper;
+/// #[derive(serde::Serialize, serde::Deserialize, rbatis::CRUDTable)]
+/// pub struct BizActivity {
+///     #[column(id)]
+///     pub id: Option<String>,
+///     pub name: Option<String>,
+///     pub status: Option<i32>,
+/// }
+/// let w = QueryWrapper::new()
+///     .eq(BizActivity::status(), 1)
+///     .like(BizActivity::name(), "test")
+///     .or(|w| w.is_null(BizActivity::name()))
+///     .order_by(BizActivity::id(), false)
+///     .limit(10);
+/// //BizActivity::select_by_wrapper(&mut rb, &w).await;
+/// ```
+///
+/// the untyped `QueryWrapper` takes `&str` identifiers, for the `crud!` tables
+/// and `rbatis::table::select_by_wrapper`:
+/// ```rust
+/// use rbatis::sql::QueryWrapper;
+/// let w = QueryWrapper::new().eq("status", 1).like("name", "test");
+/// ```
+pub struct QueryWrapper<T = ()> {
+    /// the conditions and whether each is joined with `or`, the first join is ignored
+    pub conditions: Vec<(bool, Condition)>,
+    pub group_by: Vec<String>,
+    /// the columns and `asc`
+    pub order_by: Vec<(String, bool)>,
+    pub offset: u64,
+    pub limit: Option<u64>,
+    table: PhantomData<fn() -> T>,
+}
+
+impl<T> Default for QueryWrapper<T> {
+    fn default() -> Self {
+        Self {
+            conditions: vec![],
+            group_by: vec![],
+            order_by: vec![],
+            offset: 0,
+            limit: None,
+            table: PhantomData,
+        }
+    }
+}
+
+impl<T> Clone for QueryWrapper<T> {
+    fn clone(&self) -> Self {
+        Self {
+            conditions: self.conditions.clone(),
+            group_by: self.group_by.clone(),
+            order_by: self.order_by.clone(),
+            offset: self.offset,
+            limit: self.limit,
+            table: PhantomData,
+        }
+    }
+}
+
+impl<T> PartialEq for QueryWrapper<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.conditions == other.conditions
+            && self.group_by == other.group_by
+            && self.order_by == other.order_by
+            && self.offset == other.offset
+            && self.limit == other.limit
+    }
+}
+
+impl<T> Debug for QueryWrapper<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QueryWrapper")
+            .field("conditions", &self.conditions)
+            .field("group_by", &self.group_by)
+            .field("order_by", &self.order_by)
+            .field("offset", &self.offset)
+            .field("limit", &self.limit)
+            .finish()
+    }
+}
+
+impl QueryWrapper {
+
+    /// the `and` of the entries of a map, `column = ?`, `column in (?,?)` of an array or `column is null`,
+    /// a `Patch::Unset` entry is skipped
+    pub fn from_map(condition: Value) -> Result<Self, Error> {
+        let m = match condition {
+            Value::Map(m) => m,
+            v => {
+                return Err(Error::from(format!(
+                    "[rbatis] the condition must be a map, but is {}",
+                    v
+                )))
+            }
+        };
+        let mut w = QueryWrapper::new();
+        for (k, v) in m {
+            let column = match k {
+                Value::String(k) => k,
+                k => return Err(Error::from(format!("[rbatis] the column must be a string, but is {}", k))),
+            };
+            w = match v {
+                Value::Array(values) => w.push(Condition::In(column, false, values)),
+                Value::Ext("Unset", _) => w,
+                Value::Null | Value::Ext("Null", _) => w.is_null(column),
+                v => w.push(Condition::Compare(column, "=", v)),
+            };
+        }
+        Ok(w)
+    }
+}
+
+impl<T> QueryWrapper<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// the same conditions of the untyped `QueryWrapper`
+    pub fn untyped(self) -> QueryWrapper {
+        QueryWrapper {
+            conditions: self.conditions,
+            group_by: self.group_by,
+            order_by: self.order_by,
+            offset: self.offset,
+            limit: self.limit,
+            table: PhantomData,
+        }
+    }
+
+    fn push(mut self, c: Condition) -> Self {
+        self.conditions.push((false, c));
+        self
+    }
+
+    fn compare<V: Serialize>(self, column: impl WrapperColumn<T>, op: &'static str, v: V) -> Self {
+        self.push(Condition::Compare(column.column_name().to_string(), op, rbs::to_value!(v)))
+    }
+
+    pub fn eq<V: Serialize>(self, column: impl WrapperColumn<T>, v: V) -> Self {
+        self.compare(column, "=", v)
+    }
+
+    pub fn ne<V: Serialize>(self, column: impl WrapperColumn<T>, v: V) -> Self {
+        self.compare(column, "<>", v)
+    }
+
+    pub fn gt<V: Serialize>(self, column: impl WrapperColumn<T>, v: V) -> Self {
+        self.compare(column, ">", v)
+    }
+
+    pub fn ge<V: Serialize>(self, column: impl WrapperColumn<T>, v: V) -> Self {
+        self.compare(column, ">=", v)
+    }
+
+    pub fn lt<V: Serialize>(self, column: impl WrapperColumn<T>, v: V) -> Self {
+        self.compare(column, "<", v)
+    }
+
+    pub fn le<V: Serialize>(self, column: impl WrapperColumn<T>, v: V) -> Self {
+        self.compare(column, "<=", v)
+    }
+
+    /// `column like '%v%'`, the `%`, `_` and `\` of `v` match themselves
+    pub fn like(self, column: impl WrapperColumn<T>, v: &str) -> Self {
+        self.compare(column, "like", format!("%{}%", escape_like(v)))
+    }
+
+    /// `column like 'v%'`, the `%`, `_` and `\` of `v` match themselves
+    pub fn like_right(self, column: impl WrapperColumn<T>, v: &str) -> Self {
+        self.compare(column, "like", format!("{}%", escape_like(v)))
+    }
+
+    /// `column not like '%v%'`, the `%`, `_` and `\` of `v` match themselves
+    pub fn not_like(self, column: impl WrapperColumn<T>, v: &str) -> Self {
+        self.compare(column, "not like", format!("%{}%", escape_like(v)))
+    }
+
+    /// `column in (?,?)`, an empty `values` matches no row
+    pub fn r#in<V: Serialize>(self, column: impl WrapperColumn<T>, values: &[V]) -> Self {
+        let values = values.iter().map(|v| rbs::to_value!(v)).collect();
+        self.push(Condition::In(column.column_name().to_string(), false, values))
+    }
+
+    /// `column not in (?,?)`, an empty `values` matches every row
+    pub fn not_in<V: Serialize>(self, column: impl WrapperColumn<T>, values: &[V]) -> Self {
+        let values = values.iter().map(|v| rbs::to_value!(v)).collect();
+        self.push(Condition::In(column.column_name().to_string(), true, values))
+    }
+
+    pub fn between<V: Serialize>(self, column: impl WrapperColumn<T>, min: V, max: V) -> Self {
+        self.push(Condition::Between(
+            column.column_name().to_string(),
+            rbs::to_value!(min),
+            rbs::to_value!(max),
+        ))
+    }
+
+    pub fn is_null(self, column: impl WrapperColumn<T>) -> Self {
+        self.push(Condition::IsNull(column.column_name().to_string(), false))
+    }
+
+    pub fn is_not_null(self, column: impl WrapperColumn<T>) -> Self {
+        self.push(Condition::IsNull(column.column_name().to_string(), true))
+    }
+
+    /// `and (..)` of the conditions `f` adds to a new wrapper
+    pub fn and<F: FnOnce(QueryWrapper<T>) -> QueryWrapper<T>>(self, f: F) -> Self {
+        self.push(Condition::Group(f(QueryWrapper::new()).untyped()))
+    }
+
+    /// `or (..)` of the conditions `f` adds to a new wrapper
+    pub fn or<F: FnOnce(QueryWrapper<T>) -> QueryWrapper<T>>(mut self, f: F) -> Self {
+        self.conditions
+            .push((true, Condition::Group(f(QueryWrapper::new()).untyped())));
+        self
+    }
+
+    pub fn group_by(mut self, column: impl WrapperColumn<T>) -> Self {
+        self.group_by.push(column.column_name().to_string());
+        self
+    }
+
+    pub fn order_by(mut self, column: impl WrapperColumn<T>, asc: bool) -> Self {
+        self.order_by.push((column.column_name().to_string(), asc));
+        self
+    }
+
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// skip `offset` rows, only with a `limit`
+    pub fn offset(mut self, offset: u64) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.conditions.is_empty()
+    }
+
+    /// the conditions joined with `and`/`or`, empty if there is none
+    pub fn condition_sql(&self, dialect: &dyn Dialect) -> Result<(String, Vec<Value>), Error> {
+        let mut sql = String::new();
+        let mut args = vec![];
+        for (i, (or, c)) in self.conditions.iter().enumerate() {
+            if i > 0 {
+                sql.push_str(if *or { " or " } else { " and " });
+            }
+            match c {
+                Condition::Compare(column, op, v) => {
+                    sql.push_str(&format!("{} {} ?", quote(dialect, column)?, op));
+                    if op.ends_with("like") {
+                        sql.push(' ');
+                        sql.push_str(dialect.like_escape());
+                    }
+                    args.push(v.clone());
+                }
+                Condition::In(_, false, values) if values.is_empty() => sql.push_str("1 = 0"),
+                Condition::In(_, true, values) if values.is_empty() => sql.push_str("1 = 1"),
+                Condition::In(column, not, values) => {
+                    sql.push_str(&format!(
+                        "{} {}in ({})",
+                        quote(dialect, column)?,
+                        if *not { "not " } else { "" },
+                        vec!["?"; values.len()].join(",")
+                    ));
+                    args.extend(values.iter().cloned());
+                }
+                Condition::Between(column, min, max) => {
+                    sql.push_str(&format!("{} between ? and ?", quote(dialect, column)?));
+                    args.push(min.clone());
+                    args.push(max.clone());
+                }
+                Condition::IsNull(column, not) => {
+                    sql.push_str(&format!(
+                        "{} is {}null",
+                        quote(dialect, column)?,
+                        if *not { "not " } else { "" }
+                    ));
+                }
+                Condition::Group(w) => {
+                    let (group_sql, group_args) = w.condition_sql(dialect)?;
+                    sql.push('(');
+                    sql.push_str(if group_sql.is_empty() { "1 = 1" } else { &group_sql });
+                    sql.push(')');
+                    args.extend(group_args);
+                }
+            }
+        }
+        Ok((sql, args))
+    }
+
+    /// `where ..`, empty if there is no condition
+    pub fn where_sql(&self, dialect: &dyn Dialect) -> Result<(String, Vec<Value>), Error> {
+        let (sql, args) = self.condition_sql(dialect)?;
+        if sql.is_empty() {
+            return Ok((sql, args));
+        }
+        Ok((format!("where {}", sql), args))
+    }
+
+    /// the `group by`, `order by` and `dialect.page_limit()` clauses, update, delete and count ignore them
+    pub fn tail_sql(&self, dialect: &dyn Dialect) -> Result<String, Error> {
+        let mut sql = vec![];
+        if !self.group_by.is_empty() {
+            let mut columns = Vec::with_capacity(self.group_by.len());
+            for c in &self.group_by {
+                columns.push(quote(dialect, c)?);
+            }
+            sql.push(format!("group by {}", columns.join(",")));
+        }
+        if !self.order_by.is_empty() {
+            let mut columns = Vec::with_capacity(self.order_by.len());
+            for (c, asc) in &self.order_by {
+                columns.push(format!("{} {}", quote(dialect, c)?, if *asc { "asc" } else { "desc" }));
+            }
+            sql.push(format!("order by {}", columns.join(",")));
+        }
+        if let Some(limit) = self.limit {
+            sql.push(dialect.page_limit(self.offset, limit, !self.order_by.is_empty()));
+        }
+        Ok(sql.join(" "))
+    }
+
+    /// `where .. group by .. order by .. limit ..`
+    pub fn sql(&self, dialect: &dyn Dialect) -> Result<(String, Vec<Value>), Error> {
+        let (mut sql, args) = self.where_sql(dialect)?;
+        let tail = self.tail_sql(dialect)?;
+        if !sql.is_empty() && !tail.is_empty() {
+            sql.push(' ');
+        }
+        sql.push_str(&tail);
+        Ok((sql, args))
+    }
+}
+
+// only a bare `a` or `a.b`, the dialect quotes it
+fn quote(dialect: &dyn Dialect, column: &str) -> Result<String, Error> {
+    if !rbatis_codegen::from_sql::is_ident(column) || column.contains(['"', '`', '[']) {
+        return Err(Error::from(format!(
+            "[rbatis] QueryWrapper column '{}' is not an identifier",
+            column
+        )));
+    }
+    Ok(dialect.quote(column))
+}
+
+/// escape the `%`, `_` and `\` of a like value with a `\`, see `Dialect::like_escape`
+fn escape_like(v: &str) -> String {
+    let mut escaped = String::with_capacity(v.len());
+    for c in v.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod test {
+    use crate::sql::wrapper::QueryWrapper;
+    use rbdc::dialect::DefaultDialect;
+    use rbs::Value;
+
+    #[test]
+    fn test_wrapper_sql() {
+        let w = QueryWrapper::new()
+            .eq("a", 1)
+            .r#in("b", &[1, 2])
+            .or(|w| w.is_null("c").between("d", 1, 2))
+            .r#in("e", &[] as &[i32])
+            .group_by("a")
+            .order_by("a", false)
+            .limit(10);
+        let (sql, args) = w.sql(&DefaultDialect {}).unwrap();
+        assert_eq!(
+            sql,
+            "where a = ? and b in (?,?) or (c is null and d between ? and ?) and 1 = 0 group by a order by a desc limit 0,10"
+        );
+        assert_eq!(args.len(), 5);
+        assert_eq!(args[0], Value::I64(1));
+        assert!(QueryWrapper::new().eq("a or 1=1", 1).sql(&DefaultDialect {}).is_err());
+        assert!(QueryWrapper::new().eq("\"a\"", 1).sql(&DefaultDialect {}).is_err());
+        assert_eq!(QueryWrapper::<()>::new().sql(&DefaultDialect {}).unwrap().0, "");
+    }
+
+    #[test]
+    fn test_wrapper_like() {
+        let w = QueryWrapper::new().like("a", "5%_\\").not_like("b", "x");
+        let (sql, args) = w.sql(&DefaultDialect {}).unwrap();
+        assert_eq!(sql, "where a like ? escape '\\\\' and b not like ? escape '\\\\'");
+        assert_eq!(args[0], Value::String("%5\\%\\_\\\\%".to_string()));
+        let (_, args) = QueryWrapper::new().like_right("a", "a_b").sql(&DefaultDialect {}).unwrap();
+        assert_eq!(args[0], Value::String("a\\_b%".to_string()));
+    }
+}
@@ -0,0 +1,985 @@
+//! the table metadata of `#[derive(CRUDTable)]` and the crud functions its methods call.
+//!
+//! ```rust
+//! #[derive(Clone, Debug, serde::Serialize, serde::Deserialize, rbatis::CRUDTable)]
+//! #[table(name = "biz_activity")]
+//! pub struct BizActivity {
+//!     #[column(id)]
+//!     pub id: Option<String>,
+//!     //more `#[column(id)]` make a composite key, `select_by_id((id, tenant_id))`
+//!     #[column(id)]
+//!     pub tenant_id: Option<i64>,
+//!     #[column(rename = "activity_name")]
+//!     pub name: Option<String>,
+//!     #[column(version)]
+//!     pub version: Option<i64>,
+//!     #[column(logic_delete)]
+//!     pub delete_flag: Option<i32>,
+//! }
+//! //use
+//! //BizActivity::insert(&mut rb, &activity).await;
+//! //BizActivity::insert_returning(&mut rb, &mut activity).await;//activity.id is the generated id
+//! //BizActivity::select_by_column(&mut rb, BizActivity::id(), "1").await;//BizActivity::COLUMNS are the names
+//! //rbatis::table::select_by_column::<BizActivity, _>(&mut rb, "id", "1").await;//by a column name
+//! //BizActivity::select_by_id(&mut rb, ("1".to_string(), 1)).await;
+//! ```
+//!
+//! a `#[relation(..)]` field is not a column, `load_<field>` sets it for a slice with one `in` query:
+//! ```rust
+//! #[derive(Clone, Debug, serde::Serialize, serde::Deserialize, rbatis::CRUDTable)]
+//! pub struct Order {
+//!     #[column(id)]
+//!     pub id: Option<i64>,
+//!     //the items whose `order_id` column is the `id`, `key = ".."` is another field than the id.
+//!     //a select has no such column, so a Vec needs #[serde(default)] (or be an Option<Vec<..>>)
+//!     #[relation(has_many, foreign_key = "order_id")]
+//!     #[serde(default)]
+//!     pub items: Vec<OrderItem>,
+//! }
+//! #[derive(Clone, Debug, serde::Serialize, serde::Deserialize, rbatis::CRUDTable)]
+//! pub struct OrderItem {
+//!     #[column(id)]
+//!     pub id: Option<i64>,
+//!     pub order_id: Option<i64>,
+//!     //the order whose `id` column (`key = ".."`, default "id") is the `order_id` field
+//!     #[relation(belongs_to, foreign_key = "order_id")]
+//!     pub order: Option<Order>,
+//! }
+//! //let mut orders = Order::select_all(&mut rb).await?;
+//! //Order::load_items(&mut rb, &mut orders).await?;
+//! ```
+use crate::decode::decode;
+use crate::crud::{
+    chunk_size_of, key_values, key_where, merge_result, update_batch_sql, upsert_sql, OnConflict,
+    id_is_null, update_value, with_id, UpdateRow,
+};
+use crate::executor::{Executor, RbatisRef};
+use crate::sql::{Page, PageRequest, QueryWrapper};
+use crate::Error;
+use rbdc::db::ExecResult;
+use rbdc::dialect::{Dialect, ReturningSyntax};
+use rbs::Value;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Debug, Formatter};
+use std::marker::PhantomData;
+
+/// a column of a `Table`, declared with `#[column(..)]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColumnMeta {
+    /// the struct field, also the serialized key
+    pub field: &'static str,
+    /// the column name, `#[column(rename = "..")]`, default the field
+    pub name: &'static str,
+    /// primary key `#[column(id)]`, skipped by insert when null, more ids make a composite key
+    pub id: bool,
+    pub skip_insert: bool,
+    pub skip_update: bool,
+    /// optimistic lock, update checks the old value and writes the old value + 1
+    pub version: bool,
+    /// delete sets it to 1, selects only return rows where it is 0
+    pub logic_delete: bool,
+}
+
+/// a typed reference to a column of table `T` with values of `V`, such as `BizActivity::name()`.
+///
+/// generated by `#[derive(CRUDTable)]` for every field, so a misspelled column is a compile error.
+/// the generated `*_by_column` methods and a `QueryWrapper<T>` only take the columns of their table `T`,
+/// the functions of `rbatis::table` and the untyped `QueryWrapper` take column names
+/// ```compile_fail
+/// #[derive(serde::Serialize, serde::Deserialize, rbatis::CRUDTable)]
+/// pub struct BizActivity {
+///     #[column(id)]
+///     pub id: Option<String>,
+///     pub name: Option<String>,
+/// }
+/// //a name of the table `BizActivity` must be `BizActivity::name()`
+/// let w = rbatis::sql::QueryWrapper::new().eq(BizActivity::id(), "1").eq("nmae", "a");
+/// ```
+pub struct Column<T, V> {
+    pub name: &'static str,
+    pub field: &'static str,
+    phantom: PhantomData<fn() -> (T, V)>,
+}
+
+impl<T, V> Column<T, V> {
+    pub const fn new(name: &'static str, field: &'static str) -> Self {
+        Self {
+            name,
+            field,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T, V> Clone for Column<T, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, V> Copy for Column<T, V> {}
+
+impl<T, V> Debug for Column<T, V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Column")
+            .field("name", &self.name)
+            .field("field", &self.field)
+            .finish()
+    }
+}
+
+impl<T, V> AsRef<str> for Column<T, V> {
+    fn as_ref(&self) -> &str {
+        self.name
+    }
+}
+
+/// the table metadata, implemented by `#[derive(CRUDTable)]`
+pub trait Table {
+    /// `#[table(name = "..")]`, default the snake case struct name
+    fn table_name() -> &'static str;
+
+    /// `#[table(schema = "..")]`
+    fn table_schema() -> Option<&'static str> {
+        None
+    }
+
+    fn table_columns() -> &'static [ColumnMeta];
+
+    /// find a column by its name or field
+    fn table_column(name: &str) -> Option<&'static ColumnMeta> {
+        Self::table_columns()
+            .iter()
+            .find(|c| c.name == name || c.field == name)
+    }
+
+    /// the quoted `schema.table`
+    fn quoted_table_name(dialect: &dyn Dialect) -> String {
+        match Self::table_schema() {
+            None => dialect.quote(Self::table_name()),
+            Some(schema) => format!(
+                "{}.{}",
+                dialect.quote(schema),
+                dialect.quote(Self::table_name())
+            ),
+        }
+    }
+}
+
+fn column<T: Table>(name: &str) -> Result<&'static ColumnMeta, Error> {
+    T::table_column(name).ok_or_else(|| {
+        Error::from(format!(
+            "[rbatis] unknown column `{}` of table `{}`",
+            name,
+            T::table_name()
+        ))
+    })
+}
+
+// `select a, b_col as b from t`, renamed columns are selected as their field
+fn select_sql<T: Table>(dialect: &dyn Dialect) -> String {
+    format!(
+        "select {} from {}",
+        select_columns::<T>(dialect),
+        T::quoted_table_name(dialect)
+    )
+}
+
+// `a, b as c` of the columns
+fn select_columns<T: Table>(dialect: &dyn Dialect) -> String {
+    let mut sql = String::new();
+    for (i, c) in T::table_columns().iter().enumerate() {
+        if i > 0 {
+            sql.push_str(", ");
+        }
+        sql.push_str(&dialect.quote(c.name));
+        if c.name != c.field {
+            sql.push_str(" as ");
+            sql.push_str(&dialect.quote(c.field));
+        }
+    }
+    sql
+}
+
+// ` where flag = 0` or ` and flag = 0` of a logic delete column
+fn logic_delete_sql<T: Table>(dialect: &dyn Dialect, has_where: bool) -> String {
+    match T::table_columns().iter().find(|c| c.logic_delete) {
+        None => String::new(),
+        Some(c) => format!(
+            " {} {} = 0",
+            if has_where { "and" } else { "where" },
+            dialect.quote(c.name)
+        ),
+    }
+}
+
+fn id_columns<T: Table>() -> Result<Vec<&'static ColumnMeta>, Error> {
+    let ids: Vec<&ColumnMeta> = T::table_columns().iter().filter(|c| c.id).collect();
+    if ids.is_empty() {
+        return Err(Error::from(format!(
+            "[rbatis] table `{}` has no #[column(id)]",
+            T::table_name()
+        )));
+    }
+    Ok(ids)
+}
+
+// the `where` of the `by` columns and the keys
+fn by_where<K: Serialize>(
+    dialect: &dyn Dialect,
+    by: &[&ColumnMeta],
+    keys: &[K],
+) -> Result<(String, Vec<Value>), Error> {
+    let names: Vec<&str> = by.iter().map(|c| c.name).collect();
+    let mut values = Vec::with_capacity(keys.len());
+    for key in keys {
+        values.push(key_values(&names, rbs::to_value(key)?)?);
+    }
+    Ok(key_where(dialect, &names, values))
+}
+
+fn version_next(v: &Value) -> Result<Value, Error> {
+    match v {
+        Value::I32(v) => Ok(Value::I32(v + 1)),
+        Value::I64(v) => Ok(Value::I64(v + 1)),
+        Value::U32(v) => Ok(Value::U32(v + 1)),
+        Value::U64(v) => Ok(Value::U64(v + 1)),
+        _ => Err(Error::from(format!("[rbatis] version must be an integer, but is {}", v))),
+    }
+}
+
+// `insert into t (..){output} values (..),(..)`
+fn insert_sql<T: Table>(
+    dialect: &dyn Dialect,
+    columns: &[&ColumnMeta],
+    values: &[Value],
+    output: &str,
+) -> (String, Vec<Value>) {
+    let mut sql = format!("insert into {} (", T::quoted_table_name(dialect));
+    for (i, c) in columns.iter().enumerate() {
+        if i > 0 {
+            sql.push(',');
+        }
+        sql.push_str(&dialect.quote(c.name));
+    }
+    sql.push(')');
+    sql.push_str(output);
+    sql.push_str(" values ");
+    let mut args = Vec::with_capacity(columns.len() * values.len());
+    for (i, v) in values.iter().enumerate() {
+        if i > 0 {
+            sql.push(',');
+        }
+        sql.push('(');
+        for (j, c) in columns.iter().enumerate() {
+            if j > 0 {
+                sql.push(',');
+            }
+            sql.push('?');
+            args.push(v[c.field].clone());
+        }
+        sql.push(')');
+    }
+    (sql, args)
+}
+
+// the inserted columns, the ids null in every table are left out
+fn insert_columns<T: Table>(values: &[Value]) -> Result<Vec<&'static ColumnMeta>, Error> {
+    let mut columns = vec![];
+    for c in T::table_columns() {
+        if c.skip_insert || (c.id && id_is_null(values, c.field)?) {
+            continue;
+        }
+        columns.push(c);
+    }
+    Ok(columns)
+}
+
+// `a, b_col as b` of all columns, `prefix` is `inserted.` of mssql output
+fn returning_sql<T: Table>(dialect: &dyn Dialect, prefix: &str) -> String {
+    let mut sql = String::new();
+    for (i, c) in T::table_columns().iter().enumerate() {
+        if i > 0 {
+            sql.push_str(", ");
+        }
+        sql.push_str(prefix);
+        sql.push_str(&dialect.quote(c.name));
+        if c.name != c.field {
+            sql.push_str(" as ");
+            sql.push_str(&dialect.quote(c.field));
+        }
+    }
+    sql
+}
+
+/// `insert into t (..) values (..),(..)`, an id is left out when it is null in every table,
+/// a batch with some null ids only is an error.
+///
+/// split into statements under `dialect.max_params()` parameters
+pub async fn insert_batch<T: Table + Serialize>(
+    rb: &mut dyn Executor,
+    tables: &[T],
+) -> Result<ExecResult, Error> {
+    insert_batch_chunked(rb, tables, 0).await
+}
+
+/// `insert_batch` of `chunk_size` tables a statement, 0 is the most under `dialect.max_params()`.
+/// the `rows_affected` are summed up, `last_insert_id` is the one of the first statement
+pub async fn insert_batch_chunked<T: Table + Serialize>(
+    rb: &mut dyn Executor,
+    tables: &[T],
+    chunk_size: usize,
+) -> Result<ExecResult, Error> {
+    if tables.is_empty() {
+        return Err(Error::from("insert can not insert empty array tables!"));
+    }
+    let values = tables
+        .iter()
+        .map(rbs::to_value)
+        .collect::<Result<Vec<Value>, _>>()?;
+    let columns = insert_columns::<T>(&values)?;
+    let chunk_size = match chunk_size {
+        0 => chunk_size_of(rb.dialect()?, columns.len()),
+        n => n,
+    };
+    let mut result = None;
+    for chunk in values.chunks(chunk_size) {
+        let (sql, args) = insert_sql::<T>(rb.dialect()?, &columns, chunk, "");
+        result = Some(merge_result(result, rb.exec(&sql, args).await?));
+    }
+    Ok(result.unwrap_or_default())
+}
+
+/// insert and write the generated keys and defaults back into `tables`.
+///
+/// the rows are returned by `RETURNING` (postgres, sqlite) or `OUTPUT INSERTED` (mssql).
+/// mysql only writes the auto increment id back, counted from `LAST_INSERT_ID()` by the row count,
+/// which needs consecutive ids (`innodb_autoinc_lock_mode` 0 or 1).
+/// `last_insert_id` of the result is the first id
+pub async fn insert_returning<T: Table + Serialize + DeserializeOwned>(
+    rb: &mut dyn Executor,
+    tables: &mut [T],
+) -> Result<ExecResult, Error> {
+    if tables.is_empty() {
+        return Err(Error::from("insert can not insert empty array tables!"));
+    }
+    let values = tables
+        .iter()
+        .map(rbs::to_value)
+        .collect::<Result<Vec<Value>, _>>()?;
+    let columns = insert_columns::<T>(&values)?;
+    let dialect = rb.dialect()?;
+    let id = id_columns::<T>().ok().filter(|ids| ids.len() == 1).map(|ids| ids[0]);
+    let sql = match dialect.returning() {
+        ReturningSyntax::Returning => {
+            let (sql, args) = insert_sql::<T>(dialect, &columns, &values, "");
+            Some((format!("{} returning {}", sql, returning_sql::<T>(dialect, "")), args))
+        }
+        ReturningSyntax::Output => {
+            let output = format!(" output {}", returning_sql::<T>(dialect, "inserted."));
+            Some(insert_sql::<T>(dialect, &columns, &values, &output))
+        }
+        ReturningSyntax::None => None,
+    };
+    match sql {
+        Some((sql, args)) => {
+            let rows: Vec<T> = decode(rb.fetch(&sql, args).await?)?;
+            if rows.len() != tables.len() {
+                return Err(Error::from(format!(
+                    "[rbatis] insert returned {} rows of {} tables",
+                    rows.len(),
+                    tables.len()
+                )));
+            }
+            for (table, row) in tables.iter_mut().zip(rows) {
+                *table = row;
+            }
+            let last_insert_id = match id {
+                Some(id) => rbs::to_value(&tables[0])?[id.field].clone(),
+                None => Value::Null,
+            };
+            Ok(ExecResult {
+                rows_affected: tables.len() as u64,
+                last_insert_id,
+            })
+        }
+        None => {
+            // an id the table can not hold fails before the insert
+            let generated = id.filter(|id| values[0][id.field].is_null());
+            if let Some(id) = generated {
+                with_id(&tables[0], id.field, 1)?;
+            }
+            let (sql, args) = insert_sql::<T>(dialect, &columns, &values, "");
+            let r = rb.exec(&sql, args).await?;
+            if let (Some(id), Some(first)) = (generated, r.last_insert_id.as_u64()) {
+                if first > 0 {
+                    let n = (r.rows_affected as usize).min(tables.len());
+                    for (i, table) in tables.iter_mut().take(n).enumerate() {
+                        *table = with_id(&*table, id.field, first + i as u64)?;
+                    }
+                }
+            }
+            Ok(r)
+        }
+    }
+}
+
+/// insert, or update the rows conflicting on the `conflict_columns` per `on_conflict`
+pub async fn insert_or_update<T: Table + Serialize>(
+    rb: &mut dyn Executor,
+    tables: &[T],
+    conflict_columns: &[&str],
+    on_conflict: OnConflict,
+) -> Result<ExecResult, Error> {
+    if tables.is_empty() {
+        return Err(Error::from("insert can not insert empty array tables!"));
+    }
+    let values = tables
+        .iter()
+        .map(rbs::to_value)
+        .collect::<Result<Vec<Value>, _>>()?;
+    let columns = insert_columns::<T>(&values)?;
+    let names: Vec<&str> = columns.iter().map(|c| c.name).collect();
+    let rows: Vec<Vec<Value>> = values
+        .iter()
+        .map(|v| columns.iter().map(|c| v[c.field].clone()).collect())
+        .collect();
+    let conflict = conflict_columns
+        .iter()
+        .map(|c| Ok(self::column::<T>(c)?.name))
+        .collect::<Result<Vec<&str>, Error>>()?;
+    let on_conflict = match on_conflict {
+        OnConflict::UpdateColumns(updates) => OnConflict::UpdateColumns(
+            updates
+                .iter()
+                .map(|c| Ok(self::column::<T>(c)?.name.to_string()))
+                .collect::<Result<Vec<String>, Error>>()?,
+        ),
+        on_conflict => on_conflict,
+    };
+    let mut result = None;
+    for chunk in rows.chunks(chunk_size_of(rb.dialect()?, names.len())) {
+        let dialect = rb.dialect()?;
+        let table = T::quoted_table_name(dialect);
+        let (sql, args) = upsert_sql(dialect, &table, &names, chunk.to_vec(), &conflict, &on_conflict)?;
+        result = Some(merge_result(result, rb.exec(&sql, args).await?));
+    }
+    Ok(result.unwrap_or_default())
+}
+
+pub async fn select_all<T: Table + DeserializeOwned>(rb: &mut dyn Executor) -> Result<Vec<T>, Error> {
+    let dialect = rb.dialect()?;
+    let sql = select_sql::<T>(dialect) + &logic_delete_sql::<T>(dialect, false);
+    let v = rb.fetch(&sql, vec![]).await?;
+    decode(v)
+}
+
+pub async fn select_by_column<T: Table + DeserializeOwned, V: Serialize>(
+    rb: &mut dyn Executor,
+    column: &str,
+    value: V,
+) -> Result<Vec<T>, Error> {
+    let c = self::column::<T>(column)?;
+    let dialect = rb.dialect()?;
+    let sql = format!(
+        "{} where {} = ?{}",
+        select_sql::<T>(dialect),
+        dialect.quote(c.name),
+        logic_delete_sql::<T>(dialect, true)
+    );
+    let v = rb.fetch(&sql, vec![rbs::to_value(value)?]).await?;
+    decode(v)
+}
+
+/// select by the `#[column(id)]` key, a composite key is a tuple
+pub async fn select_by_id<T: Table + DeserializeOwned, K: Serialize>(
+    rb: &mut dyn Executor,
+    id: K,
+) -> Result<Option<T>, Error> {
+    let r = select_by_id_batch::<T, K>(rb, std::slice::from_ref(&id)).await?;
+    Ok(r.into_iter().next())
+}
+
+pub async fn select_by_id_batch<T: Table + DeserializeOwned, K: Serialize>(
+    rb: &mut dyn Executor,
+    ids: &[K],
+) -> Result<Vec<T>, Error> {
+    if ids.is_empty() {
+        return Ok(vec![]);
+    }
+    let dialect = rb.dialect()?;
+    let (where_sql, args) = by_where(dialect, &id_columns::<T>()?, ids)?;
+    let sql = format!(
+        "{} {}{}",
+        select_sql::<T>(dialect),
+        where_sql,
+        logic_delete_sql::<T>(dialect, true)
+    );
+    decode(rb.fetch(&sql, args).await?)
+}
+
+/// update the not null columns, a version column is checked and incremented
+pub async fn update_by_column<T: Table + Serialize>(
+    rb: &mut dyn Executor,
+    table: &T,
+    column: &str,
+) -> Result<ExecResult, Error> {
+    let by = self::column::<T>(column)?;
+    update_by(rb, table, &[by], false).await
+}
+
+/// update every column but `skip_update`, null columns are set to null
+/// except the version and logic delete columns
+pub async fn update_by_column_with_nulls<T: Table + Serialize>(
+    rb: &mut dyn Executor,
+    table: &T,
+    column: &str,
+) -> Result<ExecResult, Error> {
+    let by = self::column::<T>(column)?;
+    update_by(rb, table, &[by], true).await
+}
+
+/// update by the `#[column(id)]` key
+pub async fn update_by_id<T: Table + Serialize>(
+    rb: &mut dyn Executor,
+    table: &T,
+) -> Result<ExecResult, Error> {
+    update_by(rb, table, &id_columns::<T>()?, false).await
+}
+
+/// see `update_by_column_batch`
+pub async fn update_by_id_batch<T: Table + Serialize>(
+    rb: &mut dyn Executor,
+    tables: &[T],
+) -> Result<ExecResult, Error> {
+    update_batch_by(rb, tables, &id_columns::<T>()?).await
+}
+
+async fn update_by<T: Table + Serialize>(
+    rb: &mut dyn Executor,
+    table: &T,
+    by: &[&ColumnMeta],
+    with_nulls: bool,
+) -> Result<ExecResult, Error> {
+    let table = rbs::to_value(table)?;
+    let dialect = rb.dialect()?;
+    let (mut sql, mut args, version) = set_sql::<T>(dialect, &table, by, with_nulls)?;
+    let names: Vec<&str> = by.iter().map(|c| c.name).collect();
+    let key = by.iter().map(|c| table[c.field].clone()).collect();
+    let (where_sql, key_args) = key_where(dialect, &names, vec![key]);
+    sql.push(' ');
+    sql.push_str(&where_sql);
+    args.extend(key_args);
+    if let Some((c, v)) = version {
+        sql.push_str(&format!(" and {} = ?", dialect.quote(c.name)));
+        args.push(v);
+    }
+    rb.exec(&sql, args).await
+}
+
+// `update t set a = ?,b = ?` of the columns but `by`, and the version column and its old value
+#[allow(clippy::type_complexity)]
+fn set_sql<T: Table>(
+    dialect: &dyn Dialect,
+    table: &Value,
+    by: &[&ColumnMeta],
+    with_nulls: bool,
+) -> Result<(String, Vec<Value>, Option<(&'static ColumnMeta, Value)>), Error> {
+    let mut sql = format!("update {} set ", T::quoted_table_name(dialect));
+    let mut args = vec![];
+    let mut version = None;
+    for c in T::table_columns() {
+        if by.contains(&c) || c.id || c.skip_update {
+            continue;
+        }
+        // a null version or logic delete flag is never written
+        let v = match update_value(&table[c.field], with_nulls) {
+            Some(v) if !((c.version || c.logic_delete) && v.is_null()) => v,
+            _ => continue,
+        };
+        if !args.is_empty() {
+            sql.push(',');
+        }
+        sql.push_str(&dialect.quote(c.name));
+        sql.push_str(" = ?");
+        if c.version {
+            args.push(version_next(&v)?);
+            version = Some((c, v));
+        } else {
+            args.push(v);
+        }
+    }
+    if args.is_empty() {
+        return Err(Error::from("[rbatis] update has no column to set!"));
+    }
+    Ok((sql, args, version))
+}
+
+/// update the not null columns of the rows matching the wrapper, which must have a condition
+pub async fn update_by_wrapper<T: Table + Serialize>(
+    rb: &mut dyn Executor,
+    table: &T,
+    w: &QueryWrapper,
+) -> Result<ExecResult, Error> {
+    if w.is_empty() {
+        return Err(Error::from("[rbatis] update_by_wrapper needs a condition!"));
+    }
+    let table = rbs::to_value(table)?;
+    let dialect = rb.dialect()?;
+    let (mut sql, mut args, version) = set_sql::<T>(dialect, &table, &[], false)?;
+    let (where_sql, where_args) = w.condition_sql(dialect)?;
+    sql.push_str(&format!(" where ({})", where_sql));
+    args.extend(where_args);
+    if let Some((c, v)) = version {
+        sql.push_str(&format!(" and {} = ?", dialect.quote(c.name)));
+        args.push(v);
+    }
+    rb.exec(&sql, args).await
+}
+
+/// one `update .. set a = case .. end` statement, or `update_by_column` of every table in a transaction
+/// if the table has a version column or the statement has more than `dialect.max_params()` parameters
+pub async fn update_by_column_batch<T: Table + Serialize>(
+    rb: &mut dyn Executor,
+    tables: &[T],
+    column: &str,
+) -> Result<ExecResult, Error> {
+    let by = self::column::<T>(column)?;
+    update_batch_by(rb, tables, &[by]).await
+}
+
+async fn update_batch_by<T: Table + Serialize>(
+    rb: &mut dyn Executor,
+    tables: &[T],
+    by: &[&ColumnMeta],
+) -> Result<ExecResult, Error> {
+    if tables.is_empty() {
+        return Ok(ExecResult::default());
+    }
+    let mut rows = Vec::with_capacity(tables.len());
+    let mut params = 0;
+    for table in tables {
+        let table = rbs::to_value(table)?;
+        let mut row = UpdateRow {
+            key: by.iter().map(|c| table[c.field].clone()).collect(),
+            sets: vec![],
+        };
+        for c in T::table_columns() {
+            if by.contains(&c) || c.id || c.skip_update {
+                continue;
+            }
+            if let Some(v) = update_value(&table[c.field], false) {
+                row.sets.push((c.name.to_string(), v));
+            }
+        }
+        params += row.sets.len() * (by.len() + 1) + by.len();
+        rows.push(row);
+    }
+    let dialect = rb.dialect()?;
+    let versioned = T::table_columns().iter().any(|c| c.version);
+    if !versioned && params <= dialect.max_params() {
+        let names: Vec<&str> = by.iter().map(|c| c.name).collect();
+        let table = T::quoted_table_name(dialect);
+        let (sql, args) = update_batch_sql(dialect, &table, &names, rows)?;
+        return rb.exec(&sql, args).await;
+    }
+    if rb.in_transaction() {
+        return update_each(rb, tables, by).await;
+    }
+    let mut tx = rb.get_rbatis().acquire_begin().await?;
+    match update_each(&mut tx, tables, by).await {
+        Ok(r) => {
+            if !tx.commit().await? {
+                return Err(Error::from("[rbatis] update batch commit fail!"));
+            }
+            Ok(r)
+        }
+        Err(e) => {
+            tx.rollback().await?;
+            Err(e)
+        }
+    }
+}
+
+async fn update_each<T: Table + Serialize>(
+    rb: &mut dyn Executor,
+    tables: &[T],
+    by: &[&ColumnMeta],
+) -> Result<ExecResult, Error> {
+    let mut rows_affected = 0;
+    for table in tables {
+        rows_affected += update_by(rb, table, by, false).await?.rows_affected;
+    }
+    Ok(ExecResult {
+        rows_affected,
+        last_insert_id: Value::Null,
+    })
+}
+
+/// delete rows, or set the logic delete column to 1
+pub async fn delete_by_column<T: Table, V: Serialize>(
+    rb: &mut dyn Executor,
+    column: &str,
+    value: V,
+) -> Result<ExecResult, Error> {
+    delete_by_column_batch::<T, V>(rb, column, std::slice::from_ref(&value)).await
+}
+
+pub async fn delete_by_column_batch<T: Table, V: Serialize>(
+    rb: &mut dyn Executor,
+    column: &str,
+    values: &[V],
+) -> Result<ExecResult, Error> {
+    let c = self::column::<T>(column)?;
+    delete_by::<T, V>(rb, &[c], values).await
+}
+
+/// delete by the `#[column(id)]` key, a composite key is a tuple
+pub async fn delete_by_id<T: Table, K: Serialize>(
+    rb: &mut dyn Executor,
+    id: K,
+) -> Result<ExecResult, Error> {
+    delete_by_id_batch::<T, K>(rb, std::slice::from_ref(&id)).await
+}
+
+pub async fn delete_by_id_batch<T: Table, K: Serialize>(
+    rb: &mut dyn Executor,
+    ids: &[K],
+) -> Result<ExecResult, Error> {
+    delete_by::<T, K>(rb, &id_columns::<T>()?, ids).await
+}
+
+async fn delete_by<T: Table, K: Serialize>(
+    rb: &mut dyn Executor,
+    by: &[&ColumnMeta],
+    keys: &[K],
+) -> Result<ExecResult, Error> {
+    let mut result = None;
+    for chunk in keys.chunks(chunk_size_of(rb.dialect()?, by.len())) {
+        let dialect = rb.dialect()?;
+        let (where_sql, args) = by_where(dialect, by, chunk)?;
+        let mut sql = match T::table_columns().iter().find(|c| c.logic_delete) {
+            None => format!("delete from {}", T::quoted_table_name(dialect)),
+            Some(flag) => format!(
+                "update {} set {} = 1",
+                T::quoted_table_name(dialect),
+                dialect.quote(flag.name)
+            ),
+        };
+        sql.push(' ');
+        sql.push_str(&where_sql);
+        result = Some(merge_result(result, rb.exec(&sql, args).await?));
+    }
+    Ok(result.unwrap_or_default())
+}
+
+// `where (..) and flag = 0` of the wrapper conditions and the logic delete column
+fn wrapper_where<T: Table>(dialect: &dyn Dialect, w: &QueryWrapper) -> Result<(String, Vec<Value>), Error> {
+    let (sql, args) = w.condition_sql(dialect)?;
+    if sql.is_empty() {
+        return Ok((logic_delete_sql::<T>(dialect, false), args));
+    }
+    Ok((format!(" where ({}){}", sql, logic_delete_sql::<T>(dialect, true)), args))
+}
+
+/// select by the wrapper, with its `group by`, `order by` and `limit`
+pub async fn select_by_wrapper<T: Table + DeserializeOwned>(
+    rb: &mut dyn Executor,
+    w: &QueryWrapper,
+) -> Result<Vec<T>, Error> {
+    let dialect = rb.dialect()?;
+    let (where_sql, args) = wrapper_where::<T>(dialect, w)?;
+    let mut sql = select_sql::<T>(dialect) + &where_sql;
+    let tail = w.tail_sql(dialect)?;
+    if !tail.is_empty() {
+        sql.push(' ');
+        sql.push_str(&tail);
+    }
+    decode(rb.fetch(&sql, args).await?)
+}
+
+pub async fn count_by_wrapper<T: Table>(rb: &mut dyn Executor, w: &QueryWrapper) -> Result<u64, Error> {
+    let dialect = rb.dialect()?;
+    let (where_sql, args) = wrapper_where::<T>(dialect, w)?;
+    let sql = format!("select count(1) as count from {}{}", T::quoted_table_name(dialect), where_sql);
+    decode(rb.fetch(&sql, args).await?)
+}
+
+/// `select 1 .. limit 1`, no row is transferred
+pub async fn exists_by_wrapper<T: Table>(rb: &mut dyn Executor, w: &QueryWrapper) -> Result<bool, Error> {
+    let dialect = rb.dialect()?;
+    let (where_sql, args) = wrapper_where::<T>(dialect, w)?;
+    let sql = format!("select 1 from {}{}", T::quoted_table_name(dialect), where_sql);
+    let rows = rb.fetch(&dialect.page_sql(&sql, 0, 1), args).await?;
+    Ok(rows.as_array().map(|rows| !rows.is_empty()).unwrap_or_default())
+}
+
+// the wrapper of a map condition, its keys are columns or fields
+fn map_wrapper<T: Table, M: Serialize>(condition: M) -> Result<QueryWrapper, Error> {
+    let condition = match rbs::to_value(condition)? {
+        Value::Map(m) => {
+            let mut columns = rbs::value::map::ValueMap::with_capacity(m.len());
+            for (k, v) in m {
+                let c = column::<T>(k.as_str().unwrap_or_default())?;
+                columns.insert(Value::String(c.name.to_string()), v);
+            }
+            Value::Map(columns)
+        }
+        v => v,
+    };
+    QueryWrapper::from_map(condition)
+}
+
+/// select by the `and` of the entries of a map, an array value is `in (..)`, a null value `is null`
+pub async fn select_by_map<T: Table + DeserializeOwned, M: Serialize>(
+    rb: &mut dyn Executor,
+    condition: M,
+) -> Result<Vec<T>, Error> {
+    select_by_wrapper(rb, &map_wrapper::<T, M>(condition)?).await
+}
+
+pub async fn count_by_map<T: Table, M: Serialize>(rb: &mut dyn Executor, condition: M) -> Result<u64, Error> {
+    count_by_wrapper::<T>(rb, &map_wrapper::<T, M>(condition)?).await
+}
+
+pub async fn exists_by_map<T: Table, M: Serialize>(rb: &mut dyn Executor, condition: M) -> Result<bool, Error> {
+    exists_by_wrapper::<T>(rb, &map_wrapper::<T, M>(condition)?).await
+}
+
+pub async fn count_by_column<T: Table, V: Serialize>(
+    rb: &mut dyn Executor,
+    column: &str,
+    value: V,
+) -> Result<u64, Error> {
+    let c = self::column::<T>(column)?;
+    count_by_wrapper::<T>(rb, &QueryWrapper::new().eq(c.name, value)).await
+}
+
+pub async fn exists_by_column<T: Table, V: Serialize>(
+    rb: &mut dyn Executor,
+    column: &str,
+    value: V,
+) -> Result<bool, Error> {
+    let c = self::column::<T>(column)?;
+    exists_by_wrapper::<T>(rb, &QueryWrapper::new().eq(c.name, value)).await
+}
+
+/// delete the rows matching the wrapper, which must have a condition
+pub async fn delete_by_wrapper<T: Table>(rb: &mut dyn Executor, w: &QueryWrapper) -> Result<ExecResult, Error> {
+    if w.is_empty() {
+        return Err(Error::from("[rbatis] delete_by_wrapper needs a condition!"));
+    }
+    let dialect = rb.dialect()?;
+    let (where_sql, args) = w.where_sql(dialect)?;
+    let sql = match T::table_columns().iter().find(|c| c.logic_delete) {
+        None => format!("delete from {} {}", T::quoted_table_name(dialect), where_sql),
+        Some(flag) => format!(
+            "update {} set {} = 1 {}",
+            T::quoted_table_name(dialect),
+            dialect.quote(flag.name),
+            where_sql
+        ),
+    };
+    rb.exec(&sql, args).await
+}
+
+/// see `rbatis::sql::select_page`, every column is sortable
+pub async fn select_page<T: Table + DeserializeOwned>(
+    rb: &mut dyn Executor,
+    page_req: &PageRequest,
+) -> Result<Page<T>, Error> {
+    let dialect = rb.dialect()?;
+    let columns = select_columns::<T>(dialect);
+    let table = T::quoted_table_name(dialect);
+    let where_sql = logic_delete_sql::<T>(dialect, false);
+    // a column sorts by its name or by the field it is selected as
+    let mut sortable = vec![];
+    for c in T::table_columns() {
+        sortable.push(c.name);
+        if c.field != c.name {
+            sortable.push(c.field);
+        }
+    }
+    crate::sql::select_page(rb, page_req, &columns, &table, &sortable, &where_sql, vec![], false).await
+}
+
+// the key of a relation value, none of null. the integers of any width and sign and the strings
+// of the same text are one key, an `Option<String>` id matches an integer foreign key
+fn relation_key(v: &Value) -> Option<String> {
+    match v {
+        Value::Null => None,
+        Value::String(s) => Some(s.clone()),
+        Value::I32(_) | Value::I64(_) | Value::U32(_) => v.as_i64().map(|i| i.to_string()),
+        Value::U64(u) => Some(u.to_string()),
+        Value::Ext(_, v) => relation_key(v),
+        v => Some(v.to_string()),
+    }
+}
+
+// the rows of `T` whose `column` is one of the not null `keys`, grouped by the key,
+// one `in` query a `dialect.max_params()` of distinct keys
+async fn select_related<T: Table + DeserializeOwned>(
+    rb: &mut dyn Executor,
+    column: &str,
+    keys: Vec<Value>,
+) -> Result<HashMap<String, Vec<T>>, Error> {
+    let c = self::column::<T>(column)?;
+    let mut seen = HashSet::new();
+    let keys: Vec<Value> = keys
+        .into_iter()
+        .filter(|k| relation_key(k).is_some_and(|key| seen.insert(key)))
+        .collect();
+    let mut related: HashMap<String, Vec<T>> = HashMap::new();
+    let max_params = rb.dialect()?.max_params().max(1);
+    for keys in keys.chunks(max_params) {
+        let dialect = rb.dialect()?;
+        let (where_sql, args) = wrapper_where::<T>(dialect, &QueryWrapper::new().r#in(c.name, keys))?;
+        let sql = select_sql::<T>(dialect) + &where_sql;
+        if let Value::Array(rows) = rb.fetch(&sql, args).await? {
+            for row in rows {
+                if let Some(key) = relation_key(&row[c.field]) {
+                    related.entry(key).or_default().push(rbs::from_value(row)?);
+                }
+            }
+        }
+    }
+    Ok(related)
+}
+
+/// one-to-many, `set` the children `C` whose `foreign_key` column is the `key` of each parent,
+/// loaded with one `in` query instead of a query a parent
+pub async fn load_has_many<P, C: Table + DeserializeOwned + Clone>(
+    rb: &mut dyn Executor,
+    parents: &mut [P],
+    key: impl Fn(&P) -> Value,
+    foreign_key: &str,
+    mut set: impl FnMut(&mut P, Vec<C>),
+) -> Result<(), Error> {
+    let keys: Vec<Value> = parents.iter().map(&key).collect();
+    let related = select_related::<C>(rb, foreign_key, keys.clone()).await?;
+    for (p, k) in parents.iter_mut().zip(keys) {
+        let children = relation_key(&k).and_then(|k| related.get(&k).cloned());
+        set(p, children.unwrap_or_default());
+    }
+    Ok(())
+}
+
+/// many-to-one, `set` the parent `C` whose `key` column is the `foreign_key` of each child,
+/// loaded with one `in` query instead of a query a child
+pub async fn load_belongs_to<P, C: Table + DeserializeOwned + Clone>(
+    rb: &mut dyn Executor,
+    children: &mut [P],
+    foreign_key: impl Fn(&P) -> Value,
+    key: &str,
+    mut set: impl FnMut(&mut P, Option<C>),
+) -> Result<(), Error> {
+    let keys: Vec<Value> = children.iter().map(&foreign_key).collect();
+    let related = select_related::<C>(rb, key, keys.clone()).await?;
+    for (c, k) in children.iter_mut().zip(keys) {
+        let parent = relation_key(&k).and_then(|k| related.get(&k)?.first().cloned());
+        set(c, parent);
+    }
+    Ok(())
+}
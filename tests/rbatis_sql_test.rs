@@ -49,7 +49,7 @@ mod test {
             m.insert("k".into(),1.into());
             Value::Map(m)
         });
-        let (sql, args) = py_select_by_condition(&mut rbs::Value::Map(arg), '$');
+        let (sql, args) = py_select_by_condition(&mut rbs::Value::Map(arg), '$').unwrap();
         println!("py->sql: {}", sql);
         println!("py->args: {}", serde_json::to_string(&args).unwrap());
     }
@@ -76,7 +76,7 @@ mod test {
             arg.insert("table".into(), "a".into());
             Value::Map(arg)
         });
-        let (sql, args) = save(&mut rbs::Value::Map(arg), '$');
+        let (sql, args) = save(&mut rbs::Value::Map(arg), '$').unwrap();
         println!("py->sql: {}", sql);
         println!("py->args: {}", serde_json::to_string(&args).unwrap());
     }